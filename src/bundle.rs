@@ -0,0 +1,330 @@
+use crate::commands::{self, ExportOutcome};
+use crate::config::AppConfig;
+use crate::models::Session;
+use crate::store::{path_to_slug, ConflictPolicy, SessionStore};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Version of the bundle format. Bundles with a newer version are rejected.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Describes the transcript inside a bundle and where it came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub bundle_version: u32,
+    pub session_id: String,
+    /// Project directory name as written by Claude Code (e.g. `-home-g-myproject`).
+    pub project_slug: String,
+    /// The project's real directory on the exporting machine, if it was resolved.
+    pub project_path: Option<String>,
+    pub title: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub exported_at: String,
+    pub message_count: usize,
+    pub size_bytes: u64,
+    /// SHA-256 of the transcript, hex encoded.
+    pub sha256: String,
+}
+
+/// A session packed into one JSON file: the manifest plus the JSONL transcript
+/// byte for byte, so importing it yields exactly the original file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionBundle {
+    pub manifest: BundleManifest,
+    pub jsonl: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportOutcome {
+    Imported,
+    /// The existing session is identical to the bundle.
+    Unchanged,
+    Skipped,
+    Overwritten,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportResult {
+    pub session_id: String,
+    pub outcome: ImportOutcome,
+    pub path: PathBuf,
+}
+
+impl SessionBundle {
+    /// Packs the session's JSONL file as stored in the projects directory.
+    pub fn from_session(store: &SessionStore, session: &Session) -> Result<Self> {
+        let path = store.get_session_file_path(&session.project_name, &session.id);
+        let jsonl =
+            fs::read_to_string(&path).with_context(|| format!("cannot read {}", path.display()))?;
+        Ok(Self {
+            manifest: BundleManifest {
+                bundle_version: BUNDLE_VERSION,
+                session_id: session.id.clone(),
+                project_slug: session.project_name.clone(),
+                project_path: (session.project_path != session.project_name)
+                    .then(|| session.project_path.clone()),
+                title: session.slug.clone(),
+                created_at: session.created_at.clone(),
+                updated_at: session.updated_at.clone(),
+                exported_at: chrono::Utc::now().to_rfc3339(),
+                message_count: session.message_count,
+                size_bytes: jsonl.len() as u64,
+                sha256: sha256_hex(&jsonl),
+            },
+            jsonl,
+        })
+    }
+
+    /// Reads a bundle and verifies its version and checksum.
+    pub fn read(path: &Path) -> Result<Self> {
+        let content =
+            fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))?;
+        let bundle: Self = serde_json::from_str(&content)
+            .with_context(|| format!("{} is not a session bundle", path.display()))?;
+        let manifest = &bundle.manifest;
+        if manifest.bundle_version > BUNDLE_VERSION {
+            bail!(
+                "bundle version {} is newer than supported version {}",
+                manifest.bundle_version,
+                BUNDLE_VERSION
+            );
+        }
+        if sha256_hex(&bundle.jsonl) != manifest.sha256 {
+            bail!("checksum mismatch: {} is corrupted", path.display());
+        }
+        // The ID becomes a file name; it must not escape the project directory
+        if manifest.session_id.is_empty()
+            || manifest.session_id.contains(['/', '\\', '.'])
+            || manifest.project_slug.is_empty()
+            || manifest.project_slug.contains(['/', '\\'])
+            || manifest.project_slug.starts_with('.')
+        {
+            bail!("invalid session ID or project in {}", path.display());
+        }
+        Ok(bundle)
+    }
+}
+
+/// Writes `<export file name>.session.json` into `export_dir`, following the
+/// configured file name pattern and collision policy. Files in `written` are not
+/// overwritten (see `commands::export_target`); the new file is added to it.
+pub fn export_bundle(
+    store: &SessionStore,
+    session: &Session,
+    export_dir: &Path,
+    config: &AppConfig,
+    written: &mut HashSet<PathBuf>,
+) -> Result<ExportOutcome> {
+    let path =
+        match commands::export_target(session, export_dir, "session.json", config, written)? {
+            Ok(path) => path,
+            Err(existing) => return Ok(ExportOutcome::skipped(existing)),
+        };
+    let bundle = SessionBundle::from_session(store, session)?;
+    fs::create_dir_all(export_dir)?;
+    fs::write(&path, serde_json::to_string_pretty(&bundle)?)?;
+    written.insert(path.clone());
+    Ok(ExportOutcome::written(path))
+}
+
+/// Imports a bundle into `projects/<slug>`. The slug comes from the manifest
+/// unless `project` names the directory the session should belong to on this
+/// machine, so `claude --resume` finds it there.
+pub fn import_bundle(
+    store: &SessionStore,
+    bundle_path: &Path,
+    project: Option<&str>,
+    on_conflict: ConflictPolicy,
+) -> Result<ImportResult> {
+    let bundle = SessionBundle::read(bundle_path)?;
+    let id = bundle.manifest.session_id.clone();
+    let slug = project.map_or_else(|| bundle.manifest.project_slug.clone(), path_to_slug);
+
+    let mut outcome = ImportOutcome::Imported;
+    let mut replaced = None;
+    if let Some(existing) = store.find_session_file(&id) {
+        let same_place = existing == store.get_session_file_path(&slug, &id);
+        let identical = fs::read_to_string(&existing).is_ok_and(|c| c == bundle.jsonl);
+        if identical && same_place {
+            return Ok(ImportResult {
+                session_id: id,
+                outcome: ImportOutcome::Unchanged,
+                path: existing,
+            });
+        }
+        match on_conflict {
+            ConflictPolicy::Abort => bail!(
+                "session {} already exists at {} (use --on-conflict skip or overwrite)",
+                id,
+                existing.display()
+            ),
+            ConflictPolicy::Skip => {
+                return Ok(ImportResult {
+                    session_id: id,
+                    outcome: ImportOutcome::Skipped,
+                    path: existing,
+                })
+            }
+            ConflictPolicy::Overwrite => {
+                outcome = ImportOutcome::Overwritten;
+                replaced = (!same_place).then_some(existing);
+            }
+            ConflictPolicy::KeepBoth | ConflictPolicy::Merge => bail!(
+                "session {} already exists; imports can only abort, skip or overwrite",
+                id
+            ),
+        }
+    }
+
+    let path = store.write_session_file(&slug, &id, &bundle.jsonl)?;
+    // A session ID must exist only once, even across projects
+    if let Some(old) = replaced {
+        fs::remove_file(old)?;
+    }
+    Ok(ImportResult {
+        session_id: id,
+        outcome,
+        path,
+    })
+}
+
+fn sha256_hex(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const JSONL: &str = concat!(
+        r#"{"type":"user","message":{"role":"user","content":"Hello"},"uuid":"u1"}"#,
+        "\n",
+        r#"{"type":"assistant","message":{"role":"assistant","content":"Hi"},"uuid":"a1"}"#,
+        "\n",
+    );
+
+    /// Store with one session `abc12345-bundle` in project `-home-g-proj`.
+    fn setup() -> (TempDir, SessionStore, Session) {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::with_base(tmp.path().join("claude"));
+        let path = store
+            .write_session_file("-home-g-proj", "abc12345-bundle", JSONL)
+            .unwrap();
+        let mut session = Session::new("abc12345-bundle".to_string(), "/home/g/proj".to_string());
+        session.project_name = "-home-g-proj".to_string();
+        session.slug = Some("my-title".to_string());
+        session.jsonl_path = path;
+        (tmp, store, session)
+    }
+
+    fn export(store: &SessionStore, session: &Session, dir: &Path) -> String {
+        let config = AppConfig::default();
+        export_bundle(store, session, dir, &config, &mut HashSet::new())
+            .unwrap()
+            .path
+    }
+
+    #[test]
+    fn test_bundle_roundtrip_is_lossless() {
+        let (tmp, store, session) = setup();
+        let file = export(&store, &session, &tmp.path().join("out"));
+        assert!(
+            file.ends_with("home-g-proj-abc12345.session.json"),
+            "{file}"
+        );
+
+        let bundle = SessionBundle::read(Path::new(&file)).unwrap();
+        assert_eq!(bundle.manifest.project_slug, "-home-g-proj");
+        assert_eq!(
+            bundle.manifest.project_path.as_deref(),
+            Some("/home/g/proj")
+        );
+        assert_eq!(bundle.manifest.title.as_deref(), Some("my-title"));
+        assert_eq!(bundle.manifest.size_bytes, JSONL.len() as u64);
+
+        // Import on a "second machine"
+        let other = SessionStore::with_base(tmp.path().join("other"));
+        let result = import_bundle(&other, Path::new(&file), None, ConflictPolicy::Abort).unwrap();
+        assert_eq!(result.outcome, ImportOutcome::Imported);
+        assert_eq!(
+            result.path,
+            other.get_session_file_path("-home-g-proj", "abc12345-bundle")
+        );
+        assert_eq!(fs::read_to_string(&result.path).unwrap(), JSONL);
+    }
+
+    #[test]
+    fn test_import_into_other_project() {
+        let (tmp, store, session) = setup();
+        let file = export(&store, &session, tmp.path());
+        let other = SessionStore::with_base(tmp.path().join("other"));
+        let result = import_bundle(
+            &other,
+            Path::new(&file),
+            Some("/Users/g/proj"),
+            ConflictPolicy::Abort,
+        )
+        .unwrap();
+        assert_eq!(
+            result.path,
+            other.get_session_file_path("-Users-g-proj", "abc12345-bundle")
+        );
+    }
+
+    #[test]
+    fn test_import_conflicts() {
+        let (tmp, store, session) = setup();
+        let file = export(&store, &session, tmp.path());
+        let file = Path::new(&file);
+
+        // Same content at the same place: nothing to do
+        let result = import_bundle(&store, file, None, ConflictPolicy::Abort).unwrap();
+        assert_eq!(result.outcome, ImportOutcome::Unchanged);
+
+        fs::write(&session.jsonl_path, "changed\n").unwrap();
+        let err = import_bundle(&store, file, None, ConflictPolicy::Abort).unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{err}");
+        let err = import_bundle(&store, file, None, ConflictPolicy::Merge).unwrap_err();
+        assert!(err.to_string().contains("only abort, skip or overwrite"), "{err}");
+        let result = import_bundle(&store, file, None, ConflictPolicy::Skip).unwrap();
+        assert_eq!(result.outcome, ImportOutcome::Skipped);
+        assert_eq!(
+            fs::read_to_string(&session.jsonl_path).unwrap(),
+            "changed\n"
+        );
+
+        // Overwriting into another project moves the session there
+        let result =
+            import_bundle(&store, file, Some("/elsewhere"), ConflictPolicy::Overwrite).unwrap();
+        assert_eq!(result.outcome, ImportOutcome::Overwritten);
+        assert!(!session.jsonl_path.exists());
+        assert_eq!(fs::read_to_string(&result.path).unwrap(), JSONL);
+    }
+
+    #[test]
+    fn test_read_rejects_corrupted_bundle() {
+        let (tmp, store, session) = setup();
+        let file = export(&store, &session, tmp.path());
+        let content = fs::read_to_string(&file).unwrap().replace("Hello", "Hallo");
+        fs::write(&file, content).unwrap();
+        let err = SessionBundle::read(Path::new(&file)).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"), "{err}");
+    }
+
+    #[test]
+    fn test_read_rejects_path_traversal() {
+        let (tmp, store, session) = setup();
+        let mut bundle = SessionBundle::from_session(&store, &session).unwrap();
+        bundle.manifest.session_id = "../../evil".to_string();
+        let file = tmp.path().join("evil.session.json");
+        fs::write(&file, serde_json::to_string(&bundle).unwrap()).unwrap();
+        assert!(SessionBundle::read(&file).is_err());
+    }
+}
@@ -0,0 +1,641 @@
+use crate::app::{App, SortDirection, SortField, Tab};
+use crate::bundle;
+use crate::commands;
+use crate::config::{AppConfig, TrashRetention};
+use crate::export::ExportFormat;
+use crate::models::{format_duration, format_timestamp, format_tokens, Session, TrashReason};
+use crate::pricing::format_cost;
+use crate::store::{ConflictPolicy, SessionStore};
+use crate::summary::{SessionDetail, SessionSummary};
+use crate::tree::ConversationTree;
+use crate::ui::format_datetime;
+use anyhow::{bail, Result};
+use clap::builder::{PossibleValue, PossibleValuesParser, TypedValueParser};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Command line interface. Without a subcommand the TUI is started.
+#[derive(Debug, Parser)]
+#[command(name = "agent-session-manager", version, about = "Manage Claude Code sessions")]
+pub struct Cli {
+    /// Print JSON instead of human-readable output
+    #[arg(long, global = true, conflicts_with = "ndjson")]
+    pub json: bool,
+    /// Print newline-delimited JSON (one session per line)
+    #[arg(long, global = true)]
+    pub ndjson: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    pub fn format(&self) -> OutputFormat {
+        if self.json {
+            OutputFormat::Json
+        } else if self.ndjson {
+            OutputFormat::Ndjson
+        } else {
+            OutputFormat::Table
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Table,
+    /// One pretty-printed JSON document.
+    Json,
+    /// One compact JSON value per line.
+    Ndjson,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// List sessions
+    List(ListArgs),
+    /// Show metadata and the active conversation branch of a session
+    Show {
+        /// Session ID or unique prefix
+        id: String,
+    },
+    /// Find sessions by ID, project, name, first prompt or conversation text
+    Search {
+        query: String,
+        #[command(flatten)]
+        list: ListArgs,
+    },
+    /// Export sessions as Markdown, HTML, JSON, text, Org or a re-importable bundle
+    Export {
+        /// Session ID or unique prefix
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        id: Option<String>,
+        /// Export all sessions
+        #[arg(long)]
+        all: bool,
+        /// Target directory (default: configured export path)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// Output format (default: configured export format)
+        #[arg(long, value_enum, conflicts_with = "bundle")]
+        format: Option<ExportFormat>,
+        /// Write a lossless bundle (transcript plus manifest) for `import`
+        #[arg(long)]
+        bundle: bool,
+        /// What to do if the file exists (default: configured policy)
+        #[arg(long, value_parser = policies(&ConflictPolicy::EXPORTS))]
+        on_collision: Option<ConflictPolicy>,
+        /// Mask API keys, tokens and passwords (default: configured setting)
+        #[arg(long, conflicts_with = "bundle")]
+        redact: bool,
+    },
+    /// Import a session bundle created with `export --bundle`
+    Import {
+        /// Path to the `.session.json` bundle
+        file: PathBuf,
+        /// Project directory on this machine (default: the project from the bundle)
+        #[arg(long)]
+        project: Option<String>,
+        /// What to do if the session already exists
+        #[arg(long, value_parser = policies(IMPORT_POLICIES), default_value = "abort")]
+        on_conflict: ConflictPolicy,
+    },
+    /// Move a session to the trash
+    Trash {
+        /// Session ID or unique prefix
+        id: String,
+        /// What to do if the trash already holds a session with this ID
+        #[arg(long, value_parser = policies(&ConflictPolicy::RESOLUTIONS))]
+        on_conflict: Option<ConflictPolicy>,
+    },
+    /// Restore a session from the trash
+    Restore {
+        /// Session ID or unique prefix
+        id: String,
+        /// What to do if the session file already exists
+        #[arg(long, value_parser = policies(&ConflictPolicy::RESOLUTIONS))]
+        on_conflict: Option<ConflictPolicy>,
+    },
+    /// Permanently delete trashed sessions per the retention policy
+    PurgeTrash {
+        /// Delete sessions trashed more than N days ago (default: configured limit)
+        #[arg(long, value_name = "N")]
+        days: Option<u32>,
+        /// Delete the oldest sessions until the trash is below N MB (default: configured limit)
+        #[arg(long, value_name = "N")]
+        max_mb: Option<u64>,
+    },
+    /// Permanently delete all sessions in the trash
+    EmptyTrash {
+        /// Required, since this cannot be undone
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    /// Use the trash instead of the active sessions
+    #[arg(long)]
+    pub trash: bool,
+    #[arg(long, value_enum, default_value_t = SortField::Date)]
+    pub sort: SortField,
+    /// Sort ascending (default: descending)
+    #[arg(long)]
+    pub asc: bool,
+}
+
+/// Runs a subcommand and writes its output to `out`.
+pub fn run(
+    command: Command,
+    format: OutputFormat,
+    store: &SessionStore,
+    config: &AppConfig,
+    out: &mut dyn Write,
+) -> Result<()> {
+    match command {
+        Command::List(args) => {
+            let sessions = list(store, config, &args, None)?;
+            print_sessions(out, &sessions, config, args.trash, format)
+        }
+        Command::Search { query, list: args } => {
+            let sessions = list(store, config, &args, Some(&query))?;
+            print_sessions(out, &sessions, config, args.trash, format)
+        }
+        Command::Show { id } => {
+            let mut sessions = store.load_sessions()?;
+            // `list --trash` lists trashed sessions, so `show` has to find them too
+            let in_trash = !sessions.iter().any(|s| s.id.starts_with(&id));
+            if in_trash {
+                sessions = store.load_trash()?;
+            }
+            show(out, find(&sessions, &id)?, config, in_trash, format)
+        }
+        Command::Export {
+            id,
+            all,
+            output,
+            format: export_format,
+            bundle,
+            on_collision,
+            redact,
+        } => {
+            let sessions = store.load_sessions()?;
+            let targets = match id {
+                Some(id) if !all => vec![find(&sessions, &id)?],
+                _ => sessions.iter().collect(),
+            };
+            let dir = output.unwrap_or_else(|| config.resolved_export_path());
+            let mut config = config.clone();
+            if let Some(policy) = on_collision {
+                config.export_on_collision = policy;
+            }
+            // Bundles are lossless by design and never redacted
+            config.redact_secrets = (config.redact_secrets || redact) && !bundle;
+            let export_format = export_format.unwrap_or(config.export_format);
+            // A failing session does not stop the others; they are reported at the end
+            let mut outcomes = Vec::new();
+            let mut failed = Vec::new();
+            let mut written = HashSet::new();
+            for session in &targets {
+                let outcome = if bundle {
+                    bundle::export_bundle(store, session, &dir, &config, &mut written)
+                } else {
+                    commands::export_session_into(
+                        session,
+                        &dir,
+                        export_format,
+                        &config,
+                        &mut written,
+                    )
+                };
+                match outcome {
+                    Ok(outcome) => outcomes.push(outcome),
+                    Err(e) => failed.push((session.id.clone(), format!("{:#}", e))),
+                }
+            }
+            let redacted = config
+                .redact_secrets
+                .then(|| outcomes.iter().filter_map(|o| o.redacted).sum::<usize>());
+            let (skipped, exported): (Vec<_>, Vec<_>) =
+                outcomes.into_iter().partition(|o| o.skipped);
+            if format == OutputFormat::Table {
+                for outcome in exported {
+                    writeln!(out, "{}", outcome.path)?;
+                }
+                for outcome in skipped {
+                    writeln!(out, "skipped (exists): {}", outcome.path)?;
+                }
+                if let Some(count) = redacted {
+                    writeln!(out, "masked {} secrets", count)?;
+                }
+                for (id, error) in &failed {
+                    writeln!(out, "failed: {}: {}", id, error)?;
+                }
+            } else {
+                let paths = |list: Vec<commands::ExportOutcome>| -> Vec<String> {
+                    list.into_iter().map(|o| o.path).collect()
+                };
+                let mut json = serde_json::json!({
+                    "exported": paths(exported),
+                    "skipped": paths(skipped),
+                    "failed": failed
+                        .iter()
+                        .map(|(id, error)| serde_json::json!({ "id": id, "error": error }))
+                        .collect::<Vec<_>>(),
+                });
+                if let Some(count) = redacted {
+                    json["redacted"] = count.into();
+                }
+                writeln!(out, "{}", json)?;
+            }
+            if !failed.is_empty() {
+                bail!("{} of {} sessions failed to export", failed.len(), targets.len());
+            }
+            Ok(())
+        }
+        Command::Import {
+            file,
+            project,
+            on_conflict,
+        } => {
+            let result = bundle::import_bundle(store, &file, project.as_deref(), on_conflict)?;
+            if format == OutputFormat::Table {
+                let verb = match result.outcome {
+                    bundle::ImportOutcome::Imported => "Imported",
+                    bundle::ImportOutcome::Unchanged => "Already present",
+                    bundle::ImportOutcome::Skipped => "Skipped existing",
+                    bundle::ImportOutcome::Overwritten => "Overwrote",
+                };
+                writeln!(out, "{} {}: {}", verb, result.session_id, result.path.display())?;
+            } else {
+                writeln!(out, "{}", serde_json::to_string(&result)?)?;
+            }
+            Ok(())
+        }
+        Command::Trash { id, on_conflict } => {
+            let sessions = store.load_sessions()?;
+            let session = find(&sessions, &id)?;
+            if let (Some(existing), None) = (store.trash_conflict(session), on_conflict) {
+                let path = existing.display();
+                bail!("{} is already in the trash: {}{}", session.id, path, CONFLICT_HINT);
+            }
+            let on_conflict = on_conflict.unwrap_or(ConflictPolicy::Abort);
+            let trashed = store.move_to_trash(session, TrashReason::Manual, on_conflict)?;
+            report_moved(out, format, "trashed", &session.id, &trashed.id, "Moved to trash")
+        }
+        Command::Restore { id, on_conflict } => {
+            let trash = store.load_trash()?;
+            let session = find(&trash, &id)?;
+            if let (Some(existing), None) = (store.restore_conflict(session), on_conflict) {
+                bail!("{} already exists{}", existing.display(), CONFLICT_HINT);
+            }
+            let on_conflict = on_conflict.unwrap_or(ConflictPolicy::Abort);
+            let restored = store.restore_session_file(session, on_conflict)?;
+            report_moved(out, format, "restored", &session.id, &restored.id, "Restored")
+        }
+        Command::PurgeTrash { days, max_mb } => {
+            let retention = TrashRetention {
+                max_age_days: days.or(config.trash_retention.max_age_days),
+                max_size_mb: max_mb.or(config.trash_retention.max_size_mb),
+            };
+            if !retention.is_enabled() {
+                bail!("no retention limit: pass --days or --max-mb, or set trash_retention");
+            }
+            let purge = store.purge_trash(&retention, chrono::Local::now())?;
+            if format == OutputFormat::Table {
+                if purge.is_empty() {
+                    writeln!(out, "Nothing to purge")?;
+                } else {
+                    writeln!(out, "{}", purge.describe())?;
+                }
+                for failure in &purge.failed {
+                    writeln!(out, "failed: {}: {}", failure.id, failure.error)?;
+                }
+            } else {
+                writeln!(out, "{}", serde_json::to_string(&purge)?)?;
+            }
+            if !purge.failed.is_empty() {
+                bail!("{} sessions could not be purged", purge.failed.len());
+            }
+            Ok(())
+        }
+        Command::EmptyTrash { yes } => {
+            if !yes {
+                bail!("refusing to delete the trash permanently without --yes");
+            }
+            let count = store.load_trash()?.len();
+            store.empty_trash()?;
+            if format == OutputFormat::Table {
+                writeln!(out, "Deleted {} session(s) from the trash", count)?;
+            } else {
+                writeln!(out, "{}", serde_json::json!({ "deleted": count }))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Sessions as the TUI list would show them: same filter, same sort order.
+fn list(
+    store: &SessionStore,
+    config: &AppConfig,
+    args: &ListArgs,
+    query: Option<&str>,
+) -> Result<Vec<Session>> {
+    let mut app = if args.trash {
+        App::new(Vec::new(), store.load_trash()?)
+    } else {
+        App::new(store.load_sessions()?, Vec::new())
+    };
+    app.config = config.clone();
+    app.current_tab = if args.trash { Tab::Trash } else { Tab::Sessions };
+    app.sort_field = args.sort;
+    app.sort_direction = if args.asc {
+        SortDirection::Ascending
+    } else {
+        SortDirection::Descending
+    };
+    if let Some(query) = query {
+        app.search_query = query.to_string();
+        app.run_full_text_search();
+    }
+    Ok(app.filtered_sessions().into_iter().cloned().collect())
+}
+
+/// Looks up a session by its full ID or an unambiguous prefix.
+fn find<'a>(sessions: &'a [Session], id: &str) -> Result<&'a Session> {
+    if let Some(session) = sessions.iter().find(|s| s.id == id) {
+        return Ok(session);
+    }
+    let matches: Vec<&Session> = sessions.iter().filter(|s| s.id.starts_with(id)).collect();
+    match matches.as_slice() {
+        [session] => Ok(session),
+        [] => bail!("no session matches '{}'", id),
+        _ => bail!("'{}' is ambiguous: {} sessions match", id, matches.len()),
+    }
+}
+
+fn print_sessions(
+    out: &mut dyn Write,
+    sessions: &[Session],
+    config: &AppConfig,
+    in_trash: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let summaries = || {
+        sessions
+            .iter()
+            .map(|s| SessionSummary::new(s, &config.pricing, in_trash))
+    };
+    match format {
+        OutputFormat::Json => {
+            let rows: Vec<SessionSummary> = summaries().collect();
+            writeln!(out, "{}", serde_json::to_string_pretty(&rows)?)?;
+            return Ok(());
+        }
+        OutputFormat::Ndjson => {
+            for summary in summaries() {
+                writeln!(out, "{}", serde_json::to_string(&summary)?)?;
+            }
+            return Ok(());
+        }
+        OutputFormat::Table => {}
+    }
+    // In the trash the deletion time matters, not the last change
+    let date_header = if in_trash { "DELETED" } else { "UPDATED" };
+    writeln!(
+        out,
+        "{:<36}  {:<24}  {:<20}  {:<16}  {:>5}  {:>7}  {:>7}",
+        "ID", "PROJECT", "NAME", date_header, "MSGS", "TOKENS", "COST"
+    )?;
+    for s in sessions {
+        writeln!(
+            out,
+            "{:<36}  {:<24}  {:<20}  {:<16}  {:>5}  {:>7}  {:>7}",
+            s.id,
+            s.display_project_name(),
+            s.slug.as_deref().unwrap_or(""),
+            format_datetime(s.list_date()),
+            s.message_count,
+            format_tokens(s.total_tokens()),
+            format_cost(config.pricing.cost(&s.token_usage))
+        )?;
+    }
+    Ok(())
+}
+
+fn show(
+    out: &mut dyn Write,
+    session: &Session,
+    config: &AppConfig,
+    in_trash: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let tree = ConversationTree::build(session.load_messages()?);
+    let branch = tree.branch_messages(tree.active_branch());
+    if format != OutputFormat::Table {
+        let summary = SessionSummary::new(session, &config.pricing, in_trash);
+        let detail = SessionDetail::new(summary, &branch);
+        let text = if format == OutputFormat::Json {
+            serde_json::to_string_pretty(&detail)?
+        } else {
+            serde_json::to_string(&detail)?
+        };
+        writeln!(out, "{}", text)?;
+        return Ok(());
+    }
+
+    writeln!(out, "Project:  {}", session.display_project_name())?;
+    writeln!(out, "Session:  {}", session.id)?;
+    if let Some(title) = &session.slug {
+        writeln!(out, "Name:     {}", title)?;
+    }
+    writeln!(out, "Updated:  {}", format_datetime(&session.updated_at))?;
+    if let Some(info) = &session.trashed {
+        writeln!(
+            out,
+            "Deleted:  {} ({})",
+            format_datetime(&info.deleted_at),
+            info.reason.label()
+        )?;
+        writeln!(out, "From:     {}", info.original_path.display())?;
+    }
+    if let Some(duration) = session.duration() {
+        writeln!(out, "Duration: {}", format_duration(duration))?;
+    }
+    writeln!(out, "Messages: {}", session.message_count)?;
+    if !session.token_usage.is_empty() {
+        writeln!(
+            out,
+            "Tokens:   {} (~{})",
+            format_tokens(session.total_tokens()),
+            format_cost(config.pricing.cost(&session.token_usage))
+        )?;
+    }
+    for msg in branch {
+        if msg.content.is_empty() {
+            continue;
+        }
+        let speaker = if msg.role == "user" { "You" } else { "Assistant" };
+        match &msg.timestamp {
+            Some(ts) => writeln!(out, "\n{} ({}):", speaker, format_timestamp(ts))?,
+            None => writeln!(out, "\n{}:", speaker)?,
+        }
+        writeln!(out, "{}", msg.content)?;
+    }
+    Ok(())
+}
+
+/// Policies `import --on-conflict` accepts.
+const IMPORT_POLICIES: &[ConflictPolicy] =
+    &[ConflictPolicy::Abort, ConflictPolicy::Skip, ConflictPolicy::Overwrite];
+
+/// Command line names of the conflict policies. `suffix` is the former name of
+/// `keep-both` for exports.
+impl ValueEnum for ConflictPolicy {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            ConflictPolicy::Abort,
+            ConflictPolicy::Skip,
+            ConflictPolicy::Overwrite,
+            ConflictPolicy::KeepBoth,
+            ConflictPolicy::Merge,
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            ConflictPolicy::Abort => PossibleValue::new("abort").help("Fail with an error"),
+            ConflictPolicy::Skip => PossibleValue::new("skip").help("Keep the existing file"),
+            ConflictPolicy::Overwrite => {
+                PossibleValue::new("overwrite").help("Replace the existing file")
+            }
+            ConflictPolicy::KeepBoth => PossibleValue::new("keep-both")
+                .alias("suffix")
+                .help("Keep both; an export gets a counter suffix, a session a new ID"),
+            ConflictPolicy::Merge => {
+                PossibleValue::new("merge").help("Combine both transcripts")
+            }
+        })
+    }
+}
+
+/// Value parser for a conflict flag that accepts only the `allowed` policies.
+fn policies(allowed: &'static [ConflictPolicy]) -> impl TypedValueParser<Value = ConflictPolicy> {
+    PossibleValuesParser::new(allowed.iter().filter_map(ValueEnum::to_possible_value))
+        .map(|name| ConflictPolicy::from_str(&name, false).expect("one of the possible values"))
+}
+
+const CONFLICT_HINT: &str = " (use --on-conflict keep-both, overwrite or merge)";
+
+/// Like `report`, but mentions the new ID if the session got one (`keep-both`).
+fn report_moved(
+    out: &mut dyn Write,
+    format: OutputFormat,
+    key: &str,
+    id: &str,
+    new_id: &str,
+    message: &str,
+) -> Result<()> {
+    if id == new_id {
+        return report(out, format, key, id, message);
+    }
+    if format == OutputFormat::Table {
+        writeln!(out, "{}: {} as {}", message, id, new_id)?;
+    } else {
+        writeln!(out, "{}", serde_json::json!({ key: new_id, "from": id }))?;
+    }
+    Ok(())
+}
+
+fn report(
+    out: &mut dyn Write,
+    format: OutputFormat,
+    key: &str,
+    id: &str,
+    message: &str,
+) -> Result<()> {
+    if format == OutputFormat::Table {
+        writeln!(out, "{}: {}", message, id)?;
+    } else {
+        writeln!(out, "{}", serde_json::json!({ key: id }))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str) -> Session {
+        Session::new(id.to_string(), "/p".to_string())
+    }
+
+    #[test]
+    fn test_find_accepts_unique_prefix() {
+        let sessions = vec![session("abc-1"), session("abd-2"), session("abc")];
+        assert_eq!(find(&sessions, "abd").unwrap().id, "abd-2");
+        // An exact match wins over longer IDs with the same prefix
+        assert_eq!(find(&sessions, "abc").unwrap().id, "abc");
+        assert!(find(&sessions, "ab").unwrap_err().to_string().contains("ambiguous"));
+        assert!(find(&sessions, "zzz").unwrap_err().to_string().contains("no session"));
+    }
+
+    #[test]
+    fn test_parse_subcommands() {
+        let cli =
+            Cli::try_parse_from(["asm", "list", "--trash", "--sort", "cost", "--json"]).unwrap();
+        assert_eq!(cli.format(), OutputFormat::Json);
+        match cli.command {
+            Some(Command::List(args)) => {
+                assert!(args.trash);
+                assert_eq!(args.sort, SortField::Cost);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        assert!(Cli::try_parse_from(["asm"]).unwrap().command.is_none());
+        let ndjson = Cli::try_parse_from(["asm", "list", "--ndjson"]).unwrap();
+        assert_eq!(ndjson.format(), OutputFormat::Ndjson);
+        assert!(Cli::try_parse_from(["asm", "list", "--json", "--ndjson"]).is_err());
+        assert!(Cli::try_parse_from(["asm", "export"]).is_err(), "needs an ID or --all");
+        assert!(Cli::try_parse_from(["asm", "export", "abc", "--all"]).is_err());
+        let both = ["asm", "export", "abc", "--format", "org", "--bundle"];
+        assert!(Cli::try_parse_from(both).is_err());
+        assert!(matches!(
+            Cli::try_parse_from(["asm", "import", "x.session.json", "--on-conflict", "skip"])
+                .unwrap()
+                .command,
+            Some(Command::Import { on_conflict: ConflictPolicy::Skip, project: None, .. })
+        ));
+        assert!(matches!(
+            Cli::try_parse_from(["asm", "import", "x.session.json"]).unwrap().command,
+            Some(Command::Import { on_conflict: ConflictPolicy::Abort, .. })
+        ));
+        assert!(matches!(
+            Cli::try_parse_from(["asm", "export", "--all", "--on-collision", "suffix"])
+                .unwrap()
+                .command,
+            Some(Command::Export { on_collision: Some(ConflictPolicy::KeepBoth), .. })
+        ));
+        assert!(matches!(
+            Cli::try_parse_from(["asm", "restore", "abc", "--on-conflict", "merge"])
+                .unwrap()
+                .command,
+            Some(Command::Restore { on_conflict: Some(ConflictPolicy::Merge), .. })
+        ));
+        // Each flag accepts only the policies its command can apply
+        assert!(Cli::try_parse_from(["asm", "import", "x", "--on-conflict", "merge"]).is_err());
+        assert!(Cli::try_parse_from(["asm", "trash", "abc", "--on-conflict", "skip"]).is_err());
+        assert!(matches!(
+            Cli::try_parse_from(["asm", "export", "--all"]).unwrap().command,
+            Some(Command::Export { all: true, id: None, .. })
+        ));
+        assert!(matches!(
+            Cli::try_parse_from(["asm", "purge-trash", "--days", "30"]).unwrap().command,
+            Some(Command::PurgeTrash { days: Some(30), max_mb: None })
+        ));
+    }
+}
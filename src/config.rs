@@ -1,3 +1,7 @@
+use crate::commands::DEFAULT_EXPORT_FILENAME;
+use crate::export::ExportFormat;
+use crate::pricing::Pricing;
+use crate::store::ConflictPolicy;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -5,12 +9,66 @@ use std::path::PathBuf;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub export_path: String,
+    /// Preselected in the export picker and used by `export` without `--format`.
+    #[serde(default)]
+    pub export_format: ExportFormat,
+    /// File name without extension; see `DEFAULT_EXPORT_FILENAME` for the placeholders.
+    #[serde(default = "default_export_filename")]
+    pub export_filename: String,
+    /// `overwrite`, `keep-both` (counter suffix), `skip` or `abort`.
+    #[serde(
+        default = "default_export_on_collision",
+        deserialize_with = "deserialize_export_policy"
+    )]
+    pub export_on_collision: ConflictPolicy,
+    /// Template file that replaces the built-in Markdown layout (see `template.rs`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_template: Option<String>,
+    /// Mask credentials in exports (see `redact.rs`).
+    #[serde(default)]
+    pub redact_secrets: bool,
+    /// Regexes masked in addition to the built-in detectors; a `secret` group
+    /// limits the mask to that part of the match.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redact_patterns: Vec<String>,
+    /// Per-model price overrides for cost estimates (USD per million tokens).
+    #[serde(default)]
+    pub pricing: Pricing,
+    /// Limits after which trashed sessions are deleted for good.
+    #[serde(default)]
+    pub trash_retention: TrashRetention,
+}
+
+/// Retention policy for the trash, applied at startup and by `purge-trash`.
+/// Both limits are off by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrashRetention {
+    /// Delete sessions that were trashed more than this many days ago.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u32>,
+    /// Keep the trash below this size, deleting the oldest sessions first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size_mb: Option<u64>,
+}
+
+impl TrashRetention {
+    pub fn is_enabled(&self) -> bool {
+        self.max_age_days.is_some() || self.max_size_mb.is_some()
+    }
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             export_path: "~/claude-exports".to_string(),
+            export_format: ExportFormat::default(),
+            export_filename: default_export_filename(),
+            export_on_collision: default_export_on_collision(),
+            export_template: None,
+            redact_secrets: false,
+            redact_patterns: Vec::new(),
+            pricing: Pricing::default(),
+            trash_retention: TrashRetention::default(),
         }
     }
 }
@@ -39,24 +97,63 @@ impl AppConfig {
     }
 
     pub fn resolved_export_path(&self) -> PathBuf {
-        if let Some(stripped) = self.export_path.strip_prefix("~/") {
-            dirs::home_dir()
-                .expect("home dir")
-                .join(stripped)
-        } else if self.export_path == "~" {
-            dirs::home_dir().expect("home dir")
-        } else {
-            PathBuf::from(&self.export_path)
-        }
+        expand_tilde(&self.export_path)
     }
 
-    fn config_path() -> PathBuf {
+    /// The configured export template, if any, with `~` expanded.
+    pub fn resolved_template_path(&self) -> Option<PathBuf> {
+        self.export_template
+            .as_deref()
+            .filter(|path| !path.is_empty())
+            .map(expand_tilde)
+    }
+
+    /// Directory holding `config.json` and other app-owned state (e.g. the session index).
+    pub fn config_dir() -> PathBuf {
         if let Ok(dir) = std::env::var("AGENT_CONFIG_DIR") {
-            return PathBuf::from(dir).join("config.json");
+            return PathBuf::from(dir);
         }
         dirs::config_dir()
             .expect("config dir")
-            .join("agent-session-manager/config.json")
+            .join("agent-session-manager")
+    }
+
+    fn config_path() -> PathBuf {
+        Self::config_dir().join("config.json")
+    }
+}
+
+fn default_export_filename() -> String {
+    DEFAULT_EXPORT_FILENAME.to_string()
+}
+
+fn default_export_on_collision() -> ConflictPolicy {
+    ConflictPolicy::Overwrite
+}
+
+/// Rejects policies in `config.json` that no export can apply, e.g. `merge`.
+fn deserialize_export_policy<'de, D>(deserializer: D) -> Result<ConflictPolicy, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let policy = ConflictPolicy::deserialize(deserializer)?;
+    if !ConflictPolicy::EXPORTS.contains(&policy) {
+        return Err(serde::de::Error::custom(
+            "export_on_collision must be overwrite, keep-both, skip or abort",
+        ));
+    }
+    Ok(policy)
+}
+
+fn expand_tilde(path: &str) -> PathBuf {
+    if let Some(stripped) = path.strip_prefix("~/") {
+        dirs::home_dir()
+            .expect("home dir")
+            .join(stripped)
+    } else if path == "~" {
+        dirs::home_dir().expect("home dir")
+    } else {
+        PathBuf::from(path)
     }
 }
 
@@ -78,6 +175,7 @@ mod tests {
 
         let config = AppConfig {
             export_path: "/custom/export/path".to_string(),
+            ..Default::default()
         };
 
         // Save manually to tmp path
@@ -90,6 +188,40 @@ mod tests {
         assert_eq!(loaded.export_path, "/custom/export/path");
     }
 
+    #[test]
+    fn test_config_without_pricing_uses_defaults() {
+        let loaded: AppConfig = serde_json::from_str(r#"{"export_path":"/x"}"#).unwrap();
+        assert_eq!(loaded.export_filename, DEFAULT_EXPORT_FILENAME);
+        assert_eq!(loaded.export_on_collision, ConflictPolicy::Overwrite);
+        // `suffix` is the former name of `keep-both`
+        let loaded: AppConfig =
+            serde_json::from_str(r#"{"export_path":"/x","export_on_collision":"suffix"}"#).unwrap();
+        assert_eq!(loaded.export_on_collision, ConflictPolicy::KeepBoth);
+        assert!(!loaded.redact_secrets && loaded.redact_patterns.is_empty());
+        assert_eq!(loaded.pricing, Pricing::default());
+        assert!(loaded.pricing.for_model("claude-sonnet-4-20250514").is_some());
+        assert!(!loaded.trash_retention.is_enabled());
+    }
+
+    #[test]
+    fn test_export_collision_rejects_merge() {
+        let result = serde_json::from_str::<AppConfig>(
+            r#"{"export_path":"/x","export_on_collision":"merge"}"#,
+        );
+        assert!(result.unwrap_err().to_string().contains("export_on_collision"));
+    }
+
+    #[test]
+    fn test_trash_retention_from_json() {
+        let loaded: AppConfig = serde_json::from_str(
+            r#"{"export_path":"/x","trash_retention":{"max_age_days":30}}"#,
+        )
+        .unwrap();
+        assert_eq!(loaded.trash_retention.max_age_days, Some(30));
+        assert_eq!(loaded.trash_retention.max_size_mb, None);
+        assert!(loaded.trash_retention.is_enabled());
+    }
+
     #[test]
     fn test_load_missing_file_returns_default() {
         // AppConfig::load() uses the real config path, but we can test
@@ -103,6 +235,7 @@ mod tests {
     fn test_resolved_export_path_expands_tilde() {
         let config = AppConfig {
             export_path: "~/my-exports".to_string(),
+            ..Default::default()
         };
         let resolved = config.resolved_export_path();
         let home = dirs::home_dir().unwrap();
@@ -110,10 +243,24 @@ mod tests {
         assert!(!resolved.to_string_lossy().contains('~'));
     }
 
+    #[test]
+    fn test_resolved_template_path() {
+        let mut config: AppConfig =
+            serde_json::from_str(r#"{"export_path":"/x","export_template":"~/t.md"}"#).unwrap();
+        assert_eq!(
+            config.resolved_template_path(),
+            Some(dirs::home_dir().unwrap().join("t.md"))
+        );
+        config.export_template = Some(String::new());
+        assert_eq!(config.resolved_template_path(), None);
+        assert!(!serde_json::to_string(&AppConfig::default()).unwrap().contains("template"));
+    }
+
     #[test]
     fn test_resolved_export_path_absolute() {
         let config = AppConfig {
             export_path: "/absolute/path".to_string(),
+            ..Default::default()
         };
         let resolved = config.resolved_export_path();
         assert_eq!(resolved, PathBuf::from("/absolute/path"));
@@ -132,6 +279,7 @@ mod tests {
     fn test_resolved_export_path_tilde_only() {
         let config = AppConfig {
             export_path: "~".to_string(),
+            ..Default::default()
         };
         let resolved = config.resolved_export_path();
         let home = dirs::home_dir().unwrap();
@@ -165,6 +313,7 @@ mod tests {
         let config_path = tmp.path().join("deep").join("nested").join("config.json");
         let config = AppConfig {
             export_path: "/test/path".to_string(),
+            ..Default::default()
         };
         // Manually replicate save logic
         std::fs::create_dir_all(config_path.parent().unwrap()).unwrap();
@@ -0,0 +1,537 @@
+use crate::html;
+use crate::models::{
+    format_duration, format_timestamp, format_tokens, Message, MessagePart, Session,
+};
+use crate::pricing::{format_cost, Pricing};
+use crate::summary::{SessionDetail, SessionSummary};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Renders a session transcript into one export format.
+pub trait Exporter {
+    /// File extension without the leading dot.
+    fn extension(&self) -> &'static str;
+    /// Renders the header and `messages` (the branch to export) as one document.
+    fn render(&self, session: &Session, messages: &[&Message], pricing: &Pricing)
+        -> Result<String>;
+}
+
+/// Export formats selectable in the picker, on the command line and in the config.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Html,
+    Json,
+    Text,
+    Org,
+}
+
+impl ExportFormat {
+    /// Order of the entries in the format picker.
+    pub const ALL: [ExportFormat; 5] = [
+        ExportFormat::Markdown,
+        ExportFormat::Html,
+        ExportFormat::Json,
+        ExportFormat::Text,
+        ExportFormat::Org,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Html => "HTML",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Text => "Plain text",
+            ExportFormat::Org => "Org-mode",
+        }
+    }
+
+    pub fn exporter(self) -> &'static dyn Exporter {
+        match self {
+            ExportFormat::Markdown => &Markdown,
+            ExportFormat::Html => &Html,
+            ExportFormat::Json => &Json,
+            ExportFormat::Text => &PlainText,
+            ExportFormat::Org => &Org,
+        }
+    }
+}
+
+/// Header fields shared by the text-based formats, in display order.
+pub fn metadata(session: &Session, pricing: &Pricing) -> Vec<(&'static str, String)> {
+    let mut rows = vec![
+        ("Project", session.project_name.clone()),
+        ("Session ID", session.id.clone()),
+        ("Created", session.created_at.clone()),
+        ("Updated", session.updated_at.clone()),
+    ];
+    if let Some(duration) = session.duration() {
+        rows.push(("Duration", format_duration(duration)));
+    }
+    rows.push(("Messages", session.message_count.to_string()));
+    if !session.token_usage.is_empty() {
+        rows.push(("Tokens", format_tokens(session.total_tokens())));
+        rows.push(("Estimated cost", format_cost(pricing.cost(&session.token_usage))));
+    }
+    rows
+}
+
+/// "1.1M tokens, $4.50" per model; models without a known price say so.
+pub fn model_usage(session: &Session, pricing: &Pricing) -> Vec<(String, String)> {
+    session
+        .token_usage
+        .iter()
+        .map(|(model, usage)| {
+            let cost = pricing
+                .for_model(model)
+                .map(|p| format_cost(p.cost(usage)))
+                .unwrap_or_else(|| "no price".to_string());
+            (model.clone(), format!("{} tokens, {}", format_tokens(usage.total()), cost))
+        })
+        .collect()
+}
+
+/// Speaker heading of a message, or `None` for tool results, which belong to
+/// the preceding tool call rather than to a "You" turn.
+fn speaker(msg: &Message) -> Option<String> {
+    if msg.is_tool_output() {
+        return None;
+    }
+    let name = if msg.role == "user" { "You" } else { "Assistant" };
+    Some(match &msg.timestamp {
+        Some(ts) => format!("{} ({})", name, format_timestamp(ts)),
+        None => name.to_string(),
+    })
+}
+
+pub struct Markdown;
+
+impl Exporter for Markdown {
+    fn extension(&self) -> &'static str {
+        "md"
+    }
+
+    fn render(
+        &self,
+        session: &Session,
+        messages: &[&Message],
+        pricing: &Pricing,
+    ) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# Session: {}", session.display_name())?;
+        writeln!(out)?;
+        for (label, value) in metadata(session, pricing) {
+            writeln!(out, "- **{}:** {}", label, value)?;
+        }
+        for (model, usage) in model_usage(session, pricing) {
+            writeln!(out, "  - {}: {}", model, usage)?;
+        }
+        writeln!(out)?;
+        writeln!(out, "---")?;
+        writeln!(out)?;
+
+        for msg in messages {
+            if let Some(speaker) = speaker(msg) {
+                writeln!(out, "## {}", speaker)?;
+                writeln!(out)?;
+            }
+            for part in &msg.parts {
+                write_markdown_part(&mut out, part)?;
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Writes one message part. Tool output goes into a collapsed `<details>` block.
+fn write_markdown_part(out: &mut String, part: &MessagePart) -> Result<()> {
+    match part {
+        MessagePart::Text { text } => {
+            writeln!(out, "{}", text)?;
+        }
+        MessagePart::Thinking { text } => {
+            writeln!(out, "> *Thinking:*")?;
+            for line in text.lines() {
+                writeln!(out, "> {}", line)?;
+            }
+        }
+        MessagePart::ToolUse { name, input, .. } => {
+            let input = serde_json::to_string_pretty(input)?;
+            let fence = code_fence(&input);
+            writeln!(out, "**Tool:** `{}`", name)?;
+            writeln!(out)?;
+            writeln!(out, "{}json\n{}\n{}", fence, input, fence)?;
+        }
+        MessagePart::ToolResult {
+            is_error, output, ..
+        } => {
+            let label = if *is_error { "Tool error" } else { "Tool output" };
+            let fence = code_fence(output);
+            writeln!(
+                out,
+                "<details><summary>{} ({} lines)</summary>",
+                label,
+                output.lines().count()
+            )?;
+            writeln!(out)?;
+            writeln!(out, "{}\n{}\n{}", fence, output, fence)?;
+            writeln!(out)?;
+            writeln!(out, "</details>")?;
+        }
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// A backtick fence longer than any backtick run inside `text`.
+fn code_fence(text: &str) -> String {
+    let longest = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+pub struct Html;
+
+impl Exporter for Html {
+    fn extension(&self) -> &'static str {
+        "html"
+    }
+
+    fn render(
+        &self,
+        session: &Session,
+        messages: &[&Message],
+        pricing: &Pricing,
+    ) -> Result<String> {
+        Ok(html::render_session(session, messages, pricing))
+    }
+}
+
+/// The `show --json` document: session summary plus the exported messages.
+pub struct Json;
+
+impl Exporter for Json {
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn render(
+        &self,
+        session: &Session,
+        messages: &[&Message],
+        pricing: &Pricing,
+    ) -> Result<String> {
+        // Sessions loaded from the trash carry their sidecar
+        let summary = SessionSummary::new(session, pricing, session.trashed.is_some());
+        let detail = SessionDetail::new(summary, messages);
+        Ok(serde_json::to_string_pretty(&detail)? + "\n")
+    }
+}
+
+/// Plain text for terminals and mail: no markup, tool calls indented below the turn.
+pub struct PlainText;
+
+impl Exporter for PlainText {
+    fn extension(&self) -> &'static str {
+        "txt"
+    }
+
+    fn render(
+        &self,
+        session: &Session,
+        messages: &[&Message],
+        pricing: &Pricing,
+    ) -> Result<String> {
+        let mut out = String::new();
+        let title = format!("Session: {}", session.display_name());
+        writeln!(out, "{}\n{}\n", title, "=".repeat(title.chars().count()))?;
+        for (label, value) in metadata(session, pricing) {
+            writeln!(out, "{:<15} {}", format!("{}:", label), value)?;
+        }
+        for (model, usage) in model_usage(session, pricing) {
+            writeln!(out, "  {}: {}", model, usage)?;
+        }
+
+        for msg in messages {
+            if let Some(speaker) = speaker(msg) {
+                writeln!(out, "\n{}\n{}", speaker, "-".repeat(speaker.chars().count()))?;
+            }
+            for part in &msg.parts {
+                match part {
+                    MessagePart::Text { text } => writeln!(out, "{}", text)?,
+                    MessagePart::Thinking { text } => {
+                        writeln!(out, "  [Thinking]")?;
+                        write_indented(&mut out, text)?;
+                    }
+                    MessagePart::ToolUse { name, input, .. } => {
+                        let summary = MessagePart::tool_input_summary(input);
+                        writeln!(out, "  [Tool: {}] {}", name, summary)?;
+                    }
+                    MessagePart::ToolResult {
+                        is_error, output, ..
+                    } => {
+                        let label = if *is_error { "Tool error" } else { "Tool output" };
+                        writeln!(out, "  [{}, {} lines]", label, output.lines().count())?;
+                        write_indented(&mut out, output)?;
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn write_indented(out: &mut String, text: &str) -> Result<()> {
+    for line in text.lines() {
+        writeln!(out, "    {}", line)?;
+    }
+    Ok(())
+}
+
+/// Org-mode: one heading per turn, tool calls and output as foldable sub-headings.
+pub struct Org;
+
+impl Exporter for Org {
+    fn extension(&self) -> &'static str {
+        "org"
+    }
+
+    fn render(
+        &self,
+        session: &Session,
+        messages: &[&Message],
+        pricing: &Pricing,
+    ) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "#+TITLE: Session: {}", session.display_name())?;
+        writeln!(out, "#+DATE: {}", session.created_at)?;
+        writeln!(out)?;
+        for (label, value) in metadata(session, pricing) {
+            writeln!(out, "- {} :: {}", label, value)?;
+        }
+        for (model, usage) in model_usage(session, pricing) {
+            writeln!(out, "  - {} :: {}", model, usage)?;
+        }
+
+        let mut in_turn = false;
+        for msg in messages {
+            if let Some(speaker) = speaker(msg) {
+                writeln!(out, "\n* {}", speaker)?;
+                in_turn = true;
+            }
+            // Tool output before the first turn still needs a parent heading
+            let sub = if in_turn { "**" } else { "*" };
+            for part in &msg.parts {
+                match part {
+                    MessagePart::Text { text } => write_org_text(&mut out, text)?,
+                    MessagePart::Thinking { text } => {
+                        writeln!(out, "{} Thinking", sub)?;
+                        write_org_block(&mut out, "QUOTE", "", text)?;
+                    }
+                    MessagePart::ToolUse { name, input, .. } => {
+                        let summary = MessagePart::tool_input_summary(input);
+                        writeln!(out, "{} Tool: ~{}~ {}", sub, name, summary)?;
+                        let input = serde_json::to_string_pretty(input)?;
+                        write_org_block(&mut out, "SRC", " json", &input)?;
+                    }
+                    MessagePart::ToolResult {
+                        is_error, output, ..
+                    } => {
+                        let label = if *is_error { "Tool error" } else { "Tool output" };
+                        writeln!(out, "{} {} ({} lines)", sub, label, output.lines().count())?;
+                        write_org_block(&mut out, "EXAMPLE", "", output)?;
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Message text with Markdown code fences turned into source blocks.
+fn write_org_text(out: &mut String, text: &str) -> Result<()> {
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let Some(lang) = line.trim_start().strip_prefix("```") else {
+            // A leading `*` would start a heading, `#+` a keyword
+            if line.starts_with('*') || line.starts_with("#+") {
+                writeln!(out, " {}", line)?;
+            } else {
+                writeln!(out, "{}", line)?;
+            }
+            continue;
+        };
+        let code: Vec<&str> = lines
+            .by_ref()
+            .take_while(|l| !l.trim_start().starts_with("```"))
+            .collect();
+        let lang = lang.trim_matches('`').trim();
+        let params = if lang.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", lang)
+        };
+        write_org_block(out, "SRC", &params, &code.join("\n"))?;
+    }
+    Ok(())
+}
+
+/// `#+BEGIN_<kind>` block; lines that Org would parse as structure get the usual `,` escape.
+fn write_org_block(out: &mut String, kind: &str, params: &str, content: &str) -> Result<()> {
+    writeln!(out, "#+BEGIN_{}{}", kind, params)?;
+    for line in content.lines() {
+        if line.starts_with('*') || line.trim_start().starts_with("#+") {
+            writeln!(out, ",{}", line)?;
+        } else {
+            writeln!(out, "{}", line)?;
+        }
+    }
+    writeln!(out, "#+END_{}", kind)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TokenUsage;
+
+    fn message(role: &str, parts: Vec<MessagePart>) -> Message {
+        Message {
+            role: role.to_string(),
+            content: String::new(),
+            parts,
+            timestamp: None,
+            uuid: None,
+            parent_uuid: None,
+        }
+    }
+
+    fn text(t: &str) -> MessagePart {
+        MessagePart::Text {
+            text: t.to_string(),
+        }
+    }
+
+    /// A user prompt, an assistant turn with a tool call and the tool's output.
+    fn transcript() -> Vec<Message> {
+        vec![
+            message("user", vec![text("List files\n* not a heading")]),
+            message(
+                "assistant",
+                vec![
+                    text("Sure:\n```sh\nls -la\n```"),
+                    MessagePart::ToolUse {
+                        id: "t1".to_string(),
+                        name: "Bash".to_string(),
+                        input: serde_json::json!({"command": "ls"}),
+                    },
+                ],
+            ),
+            message(
+                "user",
+                vec![MessagePart::ToolResult {
+                    tool_use_id: "t1".to_string(),
+                    is_error: false,
+                    output: "a.txt\n* b.txt".to_string(),
+                }],
+            ),
+        ]
+    }
+
+    fn render(format: ExportFormat) -> String {
+        let mut session = Session::new("abc12345-x".to_string(), "/home/g/proj".to_string());
+        session.project_name = "-home-g-proj".to_string();
+        session.message_count = 2;
+        session.token_usage.insert(
+            "claude-sonnet-4".to_string(),
+            TokenUsage {
+                input: 1_000_000,
+                ..Default::default()
+            },
+        );
+        let messages = transcript();
+        let refs: Vec<&Message> = messages.iter().collect();
+        format.exporter().render(&session, &refs, &Pricing::default()).unwrap()
+    }
+
+    #[test]
+    fn test_format_names_and_extensions() {
+        let extensions: Vec<&str> =
+            ExportFormat::ALL.iter().map(|f| f.exporter().extension()).collect();
+        assert_eq!(extensions, vec!["md", "html", "json", "txt", "org"]);
+        assert_eq!(serde_json::to_string(&ExportFormat::Org).unwrap(), "\"org\"");
+        assert_eq!(ExportFormat::default(), ExportFormat::Markdown);
+    }
+
+    #[test]
+    fn test_plain_text_export() {
+        let out = render(ExportFormat::Text);
+        assert!(out.starts_with("Session: "), "{out}");
+        assert!(out.contains("Session ID:     abc12345-x"));
+        assert!(out.contains("Estimated cost: $3.00"));
+        assert!(out.contains("\nYou\n---\nList files"));
+        assert!(out.contains("  [Tool: Bash] ls"));
+        assert!(out.contains("  [Tool output, 2 lines]\n    a.txt\n    * b.txt"));
+        assert!(!out.contains("**"), "no markup in plain text");
+    }
+
+    #[test]
+    fn test_org_export() {
+        let out = render(ExportFormat::Org);
+        assert!(out.starts_with("#+TITLE: Session: "));
+        assert!(out.contains("- Session ID :: abc12345-x"));
+        assert!(out.contains("\n* You\nList files\n * not a heading\n"));
+        assert!(out.contains("#+BEGIN_SRC sh\nls -la\n#+END_SRC"));
+        assert!(out.contains("** Tool: ~Bash~ ls\n#+BEGIN_SRC json\n"));
+        assert!(out.contains("** Tool output (2 lines)\n#+BEGIN_EXAMPLE\na.txt\n,* b.txt\n"));
+        // The tool result does not open a new turn
+        assert_eq!(out.matches("\n* You").count(), 1);
+    }
+
+    #[test]
+    fn test_json_export_matches_show_schema() {
+        let value: serde_json::Value = serde_json::from_str(&render(ExportFormat::Json)).unwrap();
+        assert_eq!(value["id"], "abc12345-x");
+        assert_eq!(value["schema_version"], crate::summary::SUMMARY_SCHEMA_VERSION);
+        assert_eq!(value["messages"].as_array().unwrap().len(), 3);
+        assert_eq!(value["in_trash"], false);
+    }
+
+    #[test]
+    fn test_json_export_of_trashed_session() {
+        let mut session = Session::new("abc12345-x".to_string(), "/home/g/proj".to_string());
+        session.trashed = Some(crate::models::TrashInfo {
+            deleted_at: "2026-01-01T00:00:00+00:00".to_string(),
+            original_path: "/home/g/.claude/projects/-home-g-proj/abc12345-x.jsonl".into(),
+            title: "proj".to_string(),
+            reason: crate::models::TrashReason::Manual,
+        });
+        let out = ExportFormat::Json.exporter().render(&session, &[], &Pricing::default()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(value["in_trash"], true);
+    }
+
+    #[test]
+    fn test_markdown_and_html_exports() {
+        let md = render(ExportFormat::Markdown);
+        assert!(md.contains("- **Messages:** 2"));
+        assert!(md.contains("  - claude-sonnet-4: 1.0M tokens, $3.00"), "{md}");
+        let html = render(ExportFormat::Html);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+    }
+
+    #[test]
+    fn test_code_fence_outgrows_backtick_runs() {
+        assert_eq!(code_fence("plain"), "```");
+        assert_eq!(code_fence("a ``` b"), "````");
+        assert_eq!(code_fence("`````"), "``````");
+    }
+}
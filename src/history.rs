@@ -0,0 +1,158 @@
+use crate::models::TrashReason;
+
+/// A destructive action in the TUI, recorded so it can be undone and redone.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// Sessions moved to the trash, by their ID there.
+    Trashed(Vec<(String, TrashReason)>),
+    /// Sessions restored from the trash, with the reason they had been trashed for.
+    Restored(Vec<(String, TrashReason)>),
+    /// Sessions given the custom title `to` (`None` is no title), with the
+    /// title each one had before.
+    Renamed {
+        to: Option<String>,
+        from: Vec<(String, Option<String>)>,
+    },
+    /// Sessions removed from the trash, by ID. Their files are only deleted
+    /// when the app exits, so until then they can be put back.
+    Deleted(Vec<String>),
+}
+
+impl Change {
+    /// Number of sessions affected.
+    pub fn len(&self) -> usize {
+        match self {
+            Change::Trashed(items) | Change::Restored(items) => items.len(),
+            Change::Renamed { from, .. } => from.len(),
+            Change::Deleted(ids) => ids.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Short description for the status line, e.g. "move 3 sessions to trash".
+    pub fn describe(&self) -> String {
+        let count = |n: usize| {
+            if n == 1 {
+                "1 session".to_string()
+            } else {
+                format!("{} sessions", n)
+            }
+        };
+        match self {
+            Change::Trashed(items) => format!("move {} to trash", count(items.len())),
+            Change::Restored(items) => format!("restore {}", count(items.len())),
+            Change::Renamed { to: Some(name), from } if from.len() == 1 => {
+                format!("rename to '{}'", name)
+            }
+            Change::Renamed { to: Some(name), from } => {
+                format!("rename {} to '{}'", count(from.len()), name)
+            }
+            Change::Renamed { to: None, from } => format!("clear title of {}", count(from.len())),
+            Change::Deleted(ids) => format!("delete {}", count(ids.len())),
+        }
+    }
+}
+
+/// Undo and redo stacks for the current run. Recording a new change drops
+/// everything that was undone before.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+}
+
+impl History {
+    pub fn record(&mut self, change: Change) {
+        if !change.is_empty() {
+            self.undo.push(change);
+            self.redo.clear();
+        }
+    }
+
+    /// The most recent change, to be reverted.
+    pub fn take_undo(&mut self) -> Option<Change> {
+        self.undo.pop()
+    }
+
+    /// The most recently undone change, to be applied again.
+    pub fn take_redo(&mut self) -> Option<Change> {
+        self.redo.pop()
+    }
+
+    /// Files away what an undo actually reverted, so it can be redone.
+    pub fn undone(&mut self, change: Change) {
+        if !change.is_empty() {
+            self.redo.push(change);
+        }
+    }
+
+    /// Files away what a redo actually applied, without touching the redo stack.
+    pub fn redone(&mut self, change: Change) {
+        if !change.is_empty() {
+            self.undo.push(change);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trashed(ids: &[&str]) -> Change {
+        Change::Trashed(
+            ids.iter()
+                .map(|id| (id.to_string(), TrashReason::Manual))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_record_clears_redo() {
+        let mut history = History::default();
+        history.record(trashed(&["a"]));
+        history.record(trashed(&["b"]));
+
+        let last = history.take_undo().unwrap();
+        assert_eq!(last, trashed(&["b"]));
+        history.undone(last);
+
+        let redo = history.take_redo().unwrap();
+        history.redone(redo);
+        assert_eq!(history.take_undo(), Some(trashed(&["b"])));
+        history.undone(trashed(&["b"]));
+
+        history.record(trashed(&["c"]));
+        assert_eq!(history.take_redo(), None, "a new change drops the undone ones");
+        assert_eq!(history.take_undo(), Some(trashed(&["c"])));
+        assert_eq!(history.take_undo(), Some(trashed(&["a"])));
+        assert_eq!(history.take_undo(), None);
+    }
+
+    #[test]
+    fn test_empty_changes_are_not_recorded() {
+        let mut history = History::default();
+        history.record(trashed(&[]));
+        history.undone(Change::Deleted(Vec::new()));
+        assert_eq!((history.take_undo(), history.take_redo()), (None, None));
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(trashed(&["a", "b"]).describe(), "move 2 sessions to trash");
+        let rename = Change::Renamed {
+            to: Some("notes".to_string()),
+            from: vec![("a".to_string(), None)],
+        };
+        assert_eq!(rename.describe(), "rename to 'notes'");
+        let cleared = Change::Renamed {
+            to: None,
+            from: vec![("a".to_string(), None), ("b".to_string(), None)],
+        };
+        assert_eq!(cleared.describe(), "clear title of 2 sessions");
+        let deleted = Change::Deleted(vec!["a".to_string()]);
+        assert_eq!(deleted.describe(), "delete 1 session");
+    }
+}
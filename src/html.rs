@@ -0,0 +1,504 @@
+use crate::export;
+use crate::models::{format_timestamp, Message, MessagePart, Session};
+use crate::pricing::Pricing;
+use std::fmt::Write;
+
+/// Embedded stylesheet; the exported file must work offline and without assets.
+const STYLE: &str = r#"
+:root { --bg: #f6f7f9; --fg: #1f2328; --muted: #656d76; --user: #dbeafe; --assistant: #ffffff;
+        --border: #d0d7de; --code-bg: #0d1117; --code-fg: #e6edf3; --error: #cf222e; }
+* { box-sizing: border-box; }
+body { margin: 0; background: var(--bg); color: var(--fg);
+       font: 15px/1.5 -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; }
+main { max-width: 920px; margin: 0 auto; padding: 24px 16px 64px; }
+header.meta { background: var(--assistant); border: 1px solid var(--border); border-radius: 10px;
+              padding: 16px 20px; margin-bottom: 24px; }
+header.meta h1 { font-size: 20px; margin: 0 0 12px; }
+header.meta dl { display: grid; grid-template-columns: max-content 1fr; gap: 4px 16px; margin: 0; }
+header.meta dt { color: var(--muted); }
+header.meta dd { margin: 0; overflow-wrap: anywhere; }
+.msg { border: 1px solid var(--border); border-radius: 12px; padding: 10px 16px; margin: 12px 0; }
+.msg.user { background: var(--user); margin-left: 48px; }
+.msg.assistant { background: var(--assistant); margin-right: 48px; }
+.msg.tool { background: transparent; border-style: dashed; margin-right: 48px; }
+.who { font-weight: 600; font-size: 13px; color: var(--muted); margin-bottom: 4px; }
+.who time { font-weight: normal; margin-left: 8px; }
+.text { white-space: pre-wrap; overflow-wrap: anywhere; }
+code { font: 13px/1.45 ui-monospace, SFMono-Regular, Menlo, Consolas, monospace;
+       background: rgba(127, 127, 127, .15); padding: 1px 4px; border-radius: 4px; }
+pre { background: var(--code-bg); color: var(--code-fg); padding: 12px; border-radius: 8px;
+      overflow-x: auto; margin: 8px 0; }
+pre code { background: none; padding: 0; }
+details { border: 1px solid var(--border); border-radius: 8px; margin: 8px 0;
+          background: rgba(127, 127, 127, .06); }
+details > summary { cursor: pointer; padding: 6px 10px; font-size: 13px; color: var(--muted); }
+details > :not(summary) { margin: 0 10px 10px; }
+details.error > summary { color: var(--error); }
+details.thinking .text { font-style: italic; color: var(--muted); }
+.kw { color: #ff7b72; } .str { color: #a5d6ff; } .num { color: #79c0ff; }
+.com { color: #8b949e; font-style: italic; }
+"#;
+
+/// Keywords highlighted in code blocks, shared by all languages.
+const KEYWORDS: &[&str] = &[
+    "as",
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "def",
+    "default",
+    "defer",
+    "del",
+    "do",
+    "elif",
+    "else",
+    "enum",
+    "except",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "fn",
+    "for",
+    "from",
+    "func",
+    "function",
+    "go",
+    "if",
+    "impl",
+    "import",
+    "in",
+    "interface",
+    "is",
+    "lambda",
+    "let",
+    "loop",
+    "match",
+    "mod",
+    "mut",
+    "new",
+    "nil",
+    "None",
+    "not",
+    "null",
+    "package",
+    "pass",
+    "pub",
+    "raise",
+    "return",
+    "self",
+    "Self",
+    "static",
+    "struct",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "trait",
+    "True",
+    "true",
+    "try",
+    "type",
+    "use",
+    "var",
+    "where",
+    "while",
+    "with",
+    "yield",
+    "False",
+];
+
+/// Languages whose line comments start with `#` instead of `//`.
+const HASH_COMMENT_LANGS: &[&str] = &[
+    "bash",
+    "dockerfile",
+    "makefile",
+    "perl",
+    "py",
+    "python",
+    "r",
+    "rb",
+    "ruby",
+    "sh",
+    "shell",
+    "toml",
+    "yaml",
+    "yml",
+    "zsh",
+];
+
+/// Renders a complete, self-contained HTML document for `messages` of `session`.
+pub fn render_session(session: &Session, messages: &[&Message], pricing: &Pricing) -> String {
+    let mut html = String::new();
+    let title = escape(&session.display_name());
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>Session: {title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<main>\n"
+    );
+    render_metadata(&mut html, session, pricing);
+    for msg in messages {
+        render_message(&mut html, msg);
+    }
+    html.push_str("</main>\n</body>\n</html>\n");
+    html
+}
+
+fn render_metadata(html: &mut String, session: &Session, pricing: &Pricing) {
+    let _ = writeln!(
+        html,
+        "<header class=\"meta\">\n<h1>{}</h1>\n<dl>",
+        escape(&session.display_name())
+    );
+    for (label, value) in export::metadata(session, pricing) {
+        let _ = writeln!(html, "<dt>{}</dt><dd>{}</dd>", label, escape(&value));
+    }
+    for (model, usage) in export::model_usage(session, pricing) {
+        let _ = writeln!(html, "<dt>{}</dt><dd>{}</dd>", escape(&model), escape(&usage));
+    }
+    html.push_str("</dl>\n</header>\n");
+}
+
+fn render_message(html: &mut String, msg: &Message) {
+    // Tool results belong to the preceding tool call, not to a "You" turn
+    if msg.is_tool_output() {
+        html.push_str("<section class=\"msg tool\">\n");
+    } else {
+        let (class, speaker) = if msg.role == "user" {
+            ("user", "You")
+        } else {
+            ("assistant", "Assistant")
+        };
+        let _ = write!(
+            html,
+            "<section class=\"msg {class}\">\n<div class=\"who\">{speaker}"
+        );
+        if let Some(ts) = &msg.timestamp {
+            let _ = write!(
+                html,
+                "<time datetime=\"{}\">{}</time>",
+                escape(ts),
+                escape(&format_timestamp(ts))
+            );
+        }
+        html.push_str("</div>\n");
+    }
+    for part in &msg.parts {
+        render_part(html, part);
+    }
+    html.push_str("</section>\n");
+}
+
+fn render_part(html: &mut String, part: &MessagePart) {
+    match part {
+        MessagePart::Text { text } => render_text(html, text),
+        MessagePart::Thinking { text } => {
+            html.push_str("<details class=\"thinking\"><summary>Thinking</summary>\n");
+            let _ = writeln!(html, "<div class=\"text\">{}</div>", escape(text));
+            html.push_str("</details>\n");
+        }
+        MessagePart::ToolUse { name, input, .. } => {
+            let summary = MessagePart::tool_input_summary(input);
+            let _ = writeln!(
+                html,
+                "<details class=\"tool-use\"><summary>Tool: <code>{}</code> {}</summary>",
+                escape(name),
+                escape(&summary)
+            );
+            let input = serde_json::to_string_pretty(input).unwrap_or_default();
+            let _ = writeln!(
+                html,
+                "<pre><code>{}</code></pre>",
+                highlight(&input, "json")
+            );
+            html.push_str("</details>\n");
+        }
+        MessagePart::ToolResult {
+            is_error, output, ..
+        } => {
+            let (class, label) = if *is_error {
+                ("tool-result error", "Tool error")
+            } else {
+                ("tool-result", "Tool output")
+            };
+            let _ = writeln!(
+                html,
+                "<details class=\"{}\"><summary>{} ({} lines)</summary>",
+                class,
+                label,
+                output.lines().count()
+            );
+            let _ = writeln!(html, "<pre><code>{}</code></pre>", escape(output));
+            html.push_str("</details>\n");
+        }
+    }
+}
+
+/// Plain text with fenced code blocks (```lang … ```) and `inline code`.
+fn render_text(html: &mut String, text: &str) {
+    let mut prose = String::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        let Some(fence_len) = fence_length(trimmed) else {
+            prose.push_str(line);
+            prose.push('\n');
+            continue;
+        };
+        flush_prose(html, &mut prose);
+        let lang = trimmed[fence_len..].trim().to_lowercase();
+        let mut code = String::new();
+        for line in lines.by_ref() {
+            // A closing fence must be at least as long as the opening one
+            if fence_length(line.trim()).is_some_and(|n| n >= fence_len)
+                && line.trim().chars().all(|c| c == '`')
+            {
+                break;
+            }
+            code.push_str(line);
+            code.push('\n');
+        }
+        let class = if lang.is_empty() {
+            String::new()
+        } else {
+            format!(" class=\"language-{}\"", escape(&lang))
+        };
+        let _ = writeln!(
+            html,
+            "<pre><code{}>{}</code></pre>",
+            class,
+            highlight(code.trim_end_matches('\n'), &lang)
+        );
+    }
+    flush_prose(html, &mut prose);
+}
+
+fn fence_length(line: &str) -> Option<usize> {
+    let n = line.chars().take_while(|&c| c == '`').count();
+    (n >= 3).then_some(n)
+}
+
+fn flush_prose(html: &mut String, prose: &mut String) {
+    let text = prose.trim_matches('\n');
+    if !text.is_empty() {
+        let _ = writeln!(html, "<div class=\"text\">{}</div>", inline_code(text));
+    }
+    prose.clear();
+}
+
+/// Escapes `text` and turns `…` spans into <code> elements.
+fn inline_code(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('`') {
+        let Some(len) = rest[start + 1..].find('`') else {
+            break;
+        };
+        out.push_str(&escape(&rest[..start]));
+        let _ = write!(
+            out,
+            "<code>{}</code>",
+            escape(&rest[start + 1..start + 1 + len])
+        );
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(&escape(rest));
+    out
+}
+
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Minimal syntax highlighting: comments, strings, numbers and common keywords.
+/// Good enough for reading transcripts without shipping a full grammar per language.
+/// Code without a language tag is only escaped.
+fn highlight(code: &str, lang: &str) -> String {
+    if lang.is_empty() {
+        return escape(code);
+    }
+    let line_comment = if HASH_COMMENT_LANGS.contains(&lang) {
+        "#"
+    } else if lang == "sql" {
+        "--"
+    } else {
+        "//"
+    };
+    let chars: Vec<char> = code.chars().collect();
+    let starts_with = |i: usize, pat: &str| {
+        pat.chars()
+            .enumerate()
+            .all(|(k, c)| chars.get(i + k) == Some(&c))
+    };
+    let span = |out: &mut String, class: &str, text: &[char]| {
+        let text: String = text.iter().collect();
+        let _ = write!(out, "<span class=\"{}\">{}</span>", class, escape(&text));
+    };
+
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if starts_with(i, line_comment) && !(line_comment == "#" && lang == "json") {
+            let end = chars[i..]
+                .iter()
+                .position(|&c| c == '\n')
+                .map_or(chars.len(), |p| i + p);
+            span(&mut out, "com", &chars[i..end]);
+            i = end;
+        } else if line_comment == "//" && starts_with(i, "/*") {
+            let end = (i + 2..chars.len())
+                .find(|&j| starts_with(j, "*/"))
+                .map_or(chars.len(), |j| j + 2);
+            span(&mut out, "com", &chars[i..end]);
+            i = end;
+        } else if c == '"' || c == '\'' || c == '`' {
+            let mut end = i + 1;
+            while end < chars.len() && chars[end] != c && chars[end] != '\n' {
+                end += if chars[end] == '\\' { 2 } else { 1 };
+            }
+            let end = (end + 1).min(chars.len());
+            span(&mut out, "str", &chars[i..end]);
+            i = end;
+        } else if c.is_ascii_digit() {
+            let end = (i..chars.len())
+                .find(|&j| {
+                    !(chars[j].is_ascii_alphanumeric() || chars[j] == '.' || chars[j] == '_')
+                })
+                .unwrap_or(chars.len());
+            span(&mut out, "num", &chars[i..end]);
+            i = end;
+        } else if c.is_alphabetic() || c == '_' {
+            let end = (i..chars.len())
+                .find(|&j| !(chars[j].is_alphanumeric() || chars[j] == '_'))
+                .unwrap_or(chars.len());
+            let word: String = chars[i..end].iter().collect();
+            if KEYWORDS.contains(&word.as_str()) {
+                span(&mut out, "kw", &chars[i..end]);
+            } else {
+                out.push_str(&escape(&word));
+            }
+            i = end;
+        } else {
+            out.push_str(&escape(&c.to_string()));
+            i += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, parts: Vec<MessagePart>) -> Message {
+        Message {
+            role: role.to_string(),
+            content: String::new(),
+            parts,
+            timestamp: Some("2026-01-15T10:00:00Z".to_string()),
+            uuid: None,
+            parent_uuid: None,
+        }
+    }
+
+    fn text(t: &str) -> MessagePart {
+        MessagePart::Text {
+            text: t.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r#"<a href="x">&'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&#39;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_render_text_with_code_block_and_inline_code() {
+        let mut html = String::new();
+        render_text(
+            &mut html,
+            "Run `cargo test`:\n```rust\nfn main() { let x = 1; } // done\n```\nok",
+        );
+        assert!(html.contains("Run <code>cargo test</code>:"));
+        assert!(html.contains("<pre><code class=\"language-rust\">"));
+        assert!(html.contains("<span class=\"kw\">fn</span> main"));
+        assert!(html.contains("<span class=\"num\">1</span>"));
+        assert!(html.contains("<span class=\"com\">// done</span>"));
+        assert!(html.contains("<div class=\"text\">ok</div>"));
+    }
+
+    #[test]
+    fn test_highlight_strings_and_hash_comments() {
+        let out = highlight("x = \"a # b\"  # note", "python");
+        assert!(out.contains("<span class=\"str\">&quot;a # b&quot;</span>"));
+        assert!(out.contains("<span class=\"com\"># note</span>"));
+        // Without a language nothing is marked up
+        assert_eq!(highlight("let x = 1", ""), "let x = 1");
+    }
+
+    #[test]
+    fn test_render_session_is_self_contained_and_escaped() {
+        let mut session = Session::new("abc12345-xyz".to_string(), "/home/g/proj".to_string());
+        session.message_count = 3;
+        let user = message("user", vec![text("<script>alert(1)</script>")]);
+        let assistant = message(
+            "assistant",
+            vec![
+                MessagePart::Thinking {
+                    text: "hmm".to_string(),
+                },
+                MessagePart::ToolUse {
+                    id: "t1".to_string(),
+                    name: "Bash".to_string(),
+                    input: serde_json::json!({"command": "ls -la"}),
+                },
+            ],
+        );
+        let result = message(
+            "user",
+            vec![MessagePart::ToolResult {
+                tool_use_id: "t1".to_string(),
+                is_error: true,
+                output: "no such file\nexit 1".to_string(),
+            }],
+        );
+        let html = render_session(&session, &[&user, &assistant, &result], &Pricing::default());
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<style>"));
+        assert!(
+            !html.contains("<script>"),
+            "message content must be escaped"
+        );
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(html.contains("<dt>Session ID</dt><dd>abc12345-xyz</dd>"));
+        assert!(html.contains("<section class=\"msg user\">"));
+        assert!(html.contains("<details class=\"thinking\">"));
+        assert!(html.contains("Tool: <code>Bash</code> ls -la"));
+        assert!(html.contains(
+            "<details class=\"tool-result error\"><summary>Tool error (2 lines)</summary>"
+        ));
+        assert!(html.contains("<section class=\"msg tool\">"));
+    }
+}
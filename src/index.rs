@@ -0,0 +1,187 @@
+use crate::models::TokenUsage;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Bumped whenever the shape or meaning of `IndexEntry` changes; older index files are
+/// discarded.
+const INDEX_VERSION: u32 = 6;
+
+/// Parsed fields of a single JSONL file, valid as long as mtime and size are unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub mtime_ns: u64,
+    pub size: u64,
+    pub total_entries: usize,
+    pub slug: Option<String>,
+    pub message_count: usize,
+    pub first_prompt: Option<String>,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub token_usage: BTreeMap<String, TokenUsage>,
+}
+
+/// Persistent cache of parsed session files, keyed by absolute JSONL path.
+/// Lets startup skip re-parsing files that did not change since the last run.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SessionIndex {
+    version: u32,
+    entries: HashMap<PathBuf, IndexEntry>,
+}
+
+impl SessionIndex {
+    /// Reads the index from disk. A missing, unreadable or outdated file yields an empty index.
+    pub fn load(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(_) => return Self::default(),
+        };
+        match serde_json::from_str::<SessionIndex>(&content) {
+            Ok(index) if index.version == INDEX_VERSION => index,
+            _ => Self::default(),
+        }
+    }
+
+    /// Writes the index atomically (temp file + rename) so a crash never leaves a torn file.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(self)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Returns the cached entry for `path` if it still matches the file's mtime and size.
+    pub fn lookup(&self, path: &Path, mtime_ns: u64, size: u64) -> Option<&IndexEntry> {
+        self.entries
+            .get(path)
+            .filter(|e| e.mtime_ns == mtime_ns && e.size == size)
+    }
+
+    /// Replaces all entries below `root` with `fresh`, dropping files that no longer exist.
+    /// Entries outside `root` (e.g. trash vs. projects) are left untouched.
+    /// Returns `true` if the index differs from before and should be saved.
+    pub fn replace_under(&mut self, root: &Path, fresh: Vec<(PathBuf, IndexEntry)>) -> bool {
+        let (old, keep): (HashMap<_, _>, HashMap<_, _>) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|(p, _)| p.starts_with(root));
+        let changed = self.version != INDEX_VERSION
+            || old.len() != fresh.len()
+            || fresh.iter().any(|(p, e)| {
+                old.get(p)
+                    .is_none_or(|o| o.mtime_ns != e.mtime_ns || o.size != e.size)
+            });
+        self.version = INDEX_VERSION;
+        self.entries = keep;
+        self.entries.extend(fresh);
+        changed
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// Modification time in nanoseconds since the Unix epoch, or 0 if unavailable.
+pub fn mtime_ns(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(mtime_ns: u64, size: u64) -> IndexEntry {
+        IndexEntry {
+            mtime_ns,
+            size,
+            total_entries: 2,
+            slug: Some("label".to_string()),
+            message_count: 1,
+            first_prompt: Some("hello".to_string()),
+            started_at: None,
+            ended_at: None,
+            token_usage: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_lookup_requires_matching_mtime_and_size() {
+        let mut index = SessionIndex::default();
+        let path = PathBuf::from("/p/a.jsonl");
+        index.replace_under(Path::new("/p"), vec![(path.clone(), entry(10, 100))]);
+
+        assert!(index.lookup(&path, 10, 100).is_some());
+        assert!(index.lookup(&path, 11, 100).is_none(), "changed mtime");
+        assert!(index.lookup(&path, 10, 101).is_none(), "changed size");
+        assert!(index.lookup(Path::new("/p/b.jsonl"), 10, 100).is_none());
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("nested/session-index.json");
+        let mut index = SessionIndex::default();
+        let key = PathBuf::from("/p/a.jsonl");
+        index.replace_under(Path::new("/p"), vec![(key.clone(), entry(1, 2))]);
+        index.save(&path).unwrap();
+
+        let loaded = SessionIndex::load(&path);
+        let e = loaded.lookup(&key, 1, 2).unwrap();
+        assert_eq!(e.slug.as_deref(), Some("label"));
+        assert_eq!(e.first_prompt.as_deref(), Some("hello"));
+        assert!(!tmp.path().join("nested/session-index.json.tmp").exists());
+    }
+
+    #[test]
+    fn test_load_discards_other_version() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("session-index.json");
+        fs::write(&path, r#"{"version":1,"entries":{}}"#).unwrap();
+        assert_eq!(SessionIndex::load(&path).len(), 0);
+        assert_eq!(SessionIndex::load(&tmp.path().join("missing.json")).len(), 0);
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(SessionIndex::load(&path).len(), 0);
+    }
+
+    #[test]
+    fn test_replace_under_reports_changes() {
+        let mut index = SessionIndex::default();
+        let root = Path::new("/p");
+        let key = PathBuf::from("/p/a.jsonl");
+        assert!(index.replace_under(root, vec![(key.clone(), entry(1, 1))]));
+        assert!(!index.replace_under(root, vec![(key.clone(), entry(1, 1))]));
+        assert!(index.replace_under(root, vec![(key, entry(2, 1))]));
+    }
+
+    #[test]
+    fn test_replace_under_keeps_other_roots() {
+        let mut index = SessionIndex::default();
+        index.replace_under(
+            Path::new("/base/projects"),
+            vec![(PathBuf::from("/base/projects/p/a.jsonl"), entry(1, 1))],
+        );
+        index.replace_under(
+            Path::new("/base/trash"),
+            vec![(PathBuf::from("/base/trash/p/b.jsonl"), entry(1, 1))],
+        );
+        // Reloading projects without a.jsonl drops it but keeps the trash entry
+        assert!(index.replace_under(Path::new("/base/projects"), Vec::new()));
+        assert_eq!(index.len(), 1);
+        assert!(index
+            .lookup(Path::new("/base/trash/p/b.jsonl"), 1, 1)
+            .is_some());
+    }
+}
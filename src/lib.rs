@@ -1,6 +1,20 @@
 pub mod app;
+pub mod bundle;
+pub mod cli;
 pub mod commands;
 pub mod config;
+pub mod export;
+pub mod history;
+pub mod html;
+pub mod index;
 pub mod models;
+pub mod pricing;
+pub mod redact;
+pub mod stats;
 pub mod store;
+pub mod summary;
+pub mod template;
+pub mod tree;
+pub mod transcript;
 pub mod ui;
+pub mod watcher;
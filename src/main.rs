@@ -1,21 +1,61 @@
 mod app;
+mod bundle;
+mod cli;
 mod commands;
 mod config;
+mod export;
+mod history;
+mod html;
+mod index;
 mod models;
+mod pricing;
+mod redact;
+mod stats;
 mod store;
+mod summary;
+mod template;
+mod tree;
+mod transcript;
 mod ui;
+mod watcher;
 
 use app::{App, Tab};
+use export::ExportFormat;
+use clap::Parser;
 use crossterm::{
-    event::{self, Event, KeyCode, MouseButton, MouseEventKind},
+    event::{self, Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::prelude::*;
 use std::error::Error;
 use std::io;
+use std::time::{Duration, Instant};
+
+/// Result of the event loop: the resume command and working directory, if any.
+type AppResult = io::Result<Option<(String, Option<String>)>>;
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let cli = cli::Cli::parse();
+    let format = cli.format();
+    if let Some(command) = cli.command {
+        // Skript-Modus: kein Raw-Mode, keine TUI
+        let store = store::SessionStore::new();
+        let config = config::AppConfig::load();
+        if let Err(err) = cli::run(command, format, &store, &config, &mut io::stdout().lock()) {
+            // Abgebrochene Pipe (z.B. `| head`) ist kein Fehler
+            if err
+                .downcast_ref::<io::Error>()
+                .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe)
+            {
+                return Ok(());
+            }
+            eprintln!("error: {:#}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, crossterm::event::EnableMouseCapture)?;
@@ -25,22 +65,39 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     // Load sessions with progress bar
     let store = store::SessionStore::new();
+    // Watcher vor dem Laden starten, damit keine Änderung während des Ladens verloren geht
+    let watcher = watcher::SessionWatcher::start(&[store.projects_path(), store.trash_path()]).ok();
     let sessions = store
-        .load_sessions_with_progress(|loaded, total| {
+        .load_sessions_with_progress(|progress| {
             let _ = terminal.draw(|f| {
-                ui::draw_loading(f, loaded, total);
+                ui::draw_loading(f, progress);
             });
         })
         .unwrap_or_default();
 
+    // Aufbewahrungsregeln anwenden, bevor der Papierkorb geladen wird
+    let retention = config::AppConfig::load().trash_retention;
+    let purge = store.purge_trash(&retention, chrono::Local::now());
     let trash = store.load_trash().unwrap_or_default();
-    let app = App::new(sessions, trash);
-    let res = run_app(&mut terminal, app);
+    let mut app = App::new(sessions, trash);
+    app.disk_usage = store.disk_usage();
+    match purge {
+        Ok(purge) if !purge.is_empty() => app.set_status(purge.describe()),
+        Ok(_) => {}
+        Err(e) => app.set_status(format!("Trash purge failed: {:#}", e)),
+    }
+    let res = run_app(&mut terminal, &mut app, &store, watcher);
 
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen, crossterm::event::DisableMouseCapture)?;
     terminal.show_cursor()?;
 
+    // Endgültiges Löschen war bis hierher rückgängig zu machen
+    let failed = app.finish_deletions();
+    if !failed.is_empty() {
+        eprintln!("Could not delete from trash: {}", failed.join(", "));
+    }
+
     match res {
         Ok(Some((command, path))) => {
             let session_id = command.strip_prefix("claude --resume ").unwrap_or_default();
@@ -86,12 +143,53 @@ fn launch_claude_resume(session_id: &str, path: Option<String>) {
     }
 }
 
+/// Mindestabstand zwischen zwei Messungen der Speicherbelegung für den Stats-Tab.
+const DISK_USAGE_INTERVAL: Duration = Duration::from_secs(5);
+
 fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
-    mut app: App,
-) -> io::Result<Option<(String, Option<String>)>> {
+    app: &mut App,
+    store: &store::SessionStore,
+    mut watcher: Option<watcher::SessionWatcher>,
+) -> AppResult {
+    let mut disk_usage_stale = false;
+    let mut disk_usage_at = Instant::now();
     loop {
-        terminal.draw(|f| ui::draw(f, &mut app))?;
+        // Live-Reload: geänderte JSONL-Dateien neu einlesen und in die Listen übernehmen
+        if let Some(watcher) = watcher.as_mut() {
+            let changes: Vec<_> = watcher
+                .poll()
+                .iter()
+                .filter_map(|path| store.reload_file(path))
+                .collect();
+            disk_usage_stale |= !changes.is_empty();
+            app.apply_session_changes(changes);
+        }
+
+        // Das Messen durchläuft beide Verzeichnisse: nur für den Stats-Tab und
+        // höchstens alle paar Sekunden, auch wenn eine aktive Session ständig schreibt
+        if disk_usage_stale
+            && app.current_tab == Tab::Stats
+            && disk_usage_at.elapsed() >= DISK_USAGE_INTERVAL
+        {
+            app.disk_usage = store.disk_usage();
+            disk_usage_stale = false;
+            disk_usage_at = Instant::now();
+        }
+
+        if let Some(format) = app.pending_bulk_export.take() {
+            bulk_export(app, format, |app| {
+                let _ = terminal.draw(|f| ui::draw(f, app));
+            });
+        }
+
+        if app.pending_secret_scan {
+            app.run_secret_scan(|app| {
+                let _ = terminal.draw(|f| ui::draw(f, app));
+            });
+        }
+
+        terminal.draw(|f| ui::draw(f, app))?;
         app.clear_expired_status();
 
         // Check if we should resume a session
@@ -106,14 +204,12 @@ fn run_app<B: Backend>(
             loop {
                 match event::read()? {
                     Event::Key(key) if key.kind == event::KeyEventKind::Press => {
-                        if let Some(result) = handle_key_event(&mut app, key) {
+                        if let Some(result) = handle_key_event(app, key) {
                             return result;
                         }
                     }
-                    Event::Mouse(mouse) => {
-                        if handle_mouse_event(&mut app, mouse) {
-                            return Ok(None);
-                        }
+                    Event::Mouse(mouse) if handle_mouse_event(app, mouse) => {
+                        return Ok(None);
                     }
                     _ => {}
                 }
@@ -131,28 +227,10 @@ fn run_app<B: Backend>(
 fn handle_key_event(
     app: &mut App,
     key: event::KeyEvent,
-) -> Option<io::Result<Option<(String, Option<String>)>>> {
+) -> Option<AppResult> {
     if app.show_rename {
         match key.code {
-            KeyCode::Enter => {
-                let new_name = app.rename_input.clone();
-                if let Some(session) = app.save_rename() {
-                    match commands::rename_session(&session, &new_name) {
-                        Ok(_) => {
-                            // Update in-memory session slug
-                            let id = session.id.clone();
-                            let slug = if new_name.is_empty() { None } else { Some(new_name.clone()) };
-                            for s in app.sessions.iter_mut().chain(app.trash.iter_mut()) {
-                                if s.id == id {
-                                    s.slug = slug.clone();
-                                }
-                            }
-                            app.set_status(format!("Renamed to: {}", new_name));
-                        }
-                        Err(_) => app.set_status("Rename failed".to_string()),
-                    }
-                }
-            }
+            KeyCode::Enter => app.save_rename(),
             KeyCode::Esc => app.cancel_rename(),
             KeyCode::Char(c) => app.rename_add_char(c),
             KeyCode::Backspace => app.rename_pop_char(),
@@ -161,6 +239,64 @@ fn handle_key_event(
         return None;
     }
 
+    if app.conflict.is_some() {
+        let resolution = ui::CONFLICT_KEYS
+            .iter()
+            .position(|&k| key.code == KeyCode::Char(k))
+            .map(|i| store::ConflictPolicy::RESOLUTIONS[i]);
+        match (resolution, key.code) {
+            (Some(resolution), _) => app.resolve_conflict(resolution),
+            (None, KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('q')) => app.cancel_conflict(),
+            _ => {}
+        }
+        return None;
+    }
+
+    if app.show_export_picker {
+        match key.code {
+            KeyCode::Up => app.export_picker_prev(),
+            KeyCode::Down => app.export_picker_next(),
+            KeyCode::Tab => app.cycle_export_scope(),
+            KeyCode::Char('r') => app.toggle_redaction(),
+            KeyCode::Enter => {
+                app.close_export_picker();
+                export_selected(app, app.picked_export_format());
+            }
+            // Ziffern wählen direkt, wie die Nummern im Picker
+            KeyCode::Char(c @ '1'..='9') => {
+                let idx = c as usize - '1' as usize;
+                if let Some(&format) = ExportFormat::ALL.get(idx) {
+                    app.close_export_picker();
+                    export_selected(app, format);
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => app.close_export_picker(),
+            _ => {}
+        }
+        return None;
+    }
+
+    if app.show_secret_scan {
+        // Bestätigung für Scrub/Trash läuft über die Statuszeile, die Liste bleibt offen
+        if app.is_confirmation_pending() {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => app.confirm_and_execute(),
+                KeyCode::Char('n') | KeyCode::Esc => app.cancel_confirmation(),
+                _ => {}
+            }
+            return None;
+        }
+        match key.code {
+            KeyCode::Up => app.secret_scan_prev(),
+            KeyCode::Down => app.secret_scan_next(),
+            KeyCode::Char('s') => app.request_scrub_secrets(),
+            KeyCode::Char('d') => app.request_trash_with_secrets(),
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('x') => app.close_secret_scan(),
+            _ => {}
+        }
+        return None;
+    }
+
     if app.show_settings {
         match key.code {
             KeyCode::Enter => app.save_settings(),
@@ -190,6 +326,8 @@ fn handle_key_event(
                 app.cancel_confirmation();
             } else if app.show_search {
                 app.show_search = false;
+            } else if key.code == KeyCode::Esc && !app.marked.is_empty() {
+                app.clear_marks();
             } else {
                 return Some(Ok(None));
             }
@@ -199,7 +337,21 @@ fn handle_key_event(
         }
         KeyCode::Char('1') if !app.show_search => app.switch_to_tab(crate::app::Tab::Sessions),
         KeyCode::Char('2') if !app.show_search => app.switch_to_tab(crate::app::Tab::Trash),
+        KeyCode::Char('3') if !app.show_search => app.switch_to_tab(crate::app::Tab::Stats),
         KeyCode::Tab if !app.show_search => app.switch_tab(),
+        // Shift+↑/↓ markiert einen Bereich, Leertaste eine Session, `a` alle gefilterten
+        KeyCode::Up if !app.show_search && key.modifiers.contains(KeyModifiers::SHIFT) => {
+            let from = app.selected_session_idx;
+            app.select_prev();
+            app.extend_marks(from);
+        }
+        KeyCode::Down if !app.show_search && key.modifiers.contains(KeyModifiers::SHIFT) => {
+            let from = app.selected_session_idx;
+            app.select_next();
+            app.extend_marks(from);
+        }
+        KeyCode::Char(' ') if !app.show_search => app.toggle_mark(),
+        KeyCode::Char('a') if !app.show_search => app.toggle_mark_all(),
         KeyCode::Up if !app.show_search => match app.focus {
             crate::app::FocusPanel::List => app.select_prev(),
             crate::app::FocusPanel::Preview => app.preview_scroll_up(1),
@@ -215,11 +367,16 @@ fn handle_key_event(
         KeyCode::Enter if !app.show_search => {
             app.switch_to_selected_session();
         }
-        KeyCode::Char('r') if !app.show_search && app.current_tab == Tab::Sessions => {
-            app.open_rename();
+        KeyCode::Char('r') if !app.show_search && key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.redo();
         }
-        KeyCode::Char('u') if !app.show_search => {
-            app.restore_selected_from_trash();
+        KeyCode::Char('r') if !app.show_search => match app.current_tab {
+            Tab::Sessions => app.open_rename(),
+            Tab::Trash => app.restore_selected_from_trash(),
+            Tab::Stats => {}
+        },
+        KeyCode::Char('u') if !app.show_search && !app.is_confirmation_pending() => {
+            app.undo();
         }
         KeyCode::Char('d') if !app.show_search => {
             if app.is_confirmation_pending() {
@@ -244,7 +401,13 @@ fn handle_key_event(
                         ConfirmAction::DeletePermanently(_) => {
                             app.confirm_and_execute();
                         }
-                        ConfirmAction::EmptyTrash | ConfirmAction::TrashZeroMessages => {
+                        ConfirmAction::EmptyTrash
+                        | ConfirmAction::TrashZeroMessages
+                        | ConfirmAction::ScrubSecrets(_)
+                        | ConfirmAction::TrashWithSecrets(_)
+                        | ConfirmAction::TrashMarked(_)
+                        | ConfirmAction::RestoreMarked(_)
+                        | ConfirmAction::DeleteMarkedPermanently(_) => {
                             app.confirm_and_execute();
                         }
                     }
@@ -274,7 +437,12 @@ fn handle_key_event(
                     }
                     ConfirmAction::DeletePermanently(_)
                     | ConfirmAction::EmptyTrash
-                    | ConfirmAction::TrashZeroMessages => {
+                    | ConfirmAction::TrashZeroMessages
+                    | ConfirmAction::ScrubSecrets(_)
+                    | ConfirmAction::TrashWithSecrets(_)
+                    | ConfirmAction::TrashMarked(_)
+                    | ConfirmAction::RestoreMarked(_)
+                    | ConfirmAction::DeleteMarkedPermanently(_) => {
                         app.confirm_and_execute();
                     }
                 }
@@ -291,20 +459,7 @@ fn handle_key_event(
             }
         }
         KeyCode::Char('e') if !app.show_search => match app.current_tab {
-            crate::app::Tab::Sessions => {
-                if let Some(session) = app.get_selected_session() {
-                    let export_dir = app.config.resolved_export_path();
-                    let session_clone = session.clone();
-                    match commands::export_session(&session_clone, &export_dir) {
-                        Ok(path) => {
-                            app.set_status(format!("Exported to {}", path));
-                        }
-                        Err(_e) => {
-                            app.set_status("Export failed".to_string());
-                        }
-                    }
-                }
-            }
+            crate::app::Tab::Sessions => app.open_export_picker(),
             crate::app::Tab::Trash => {
                 if app.is_confirmation_pending() {
                     app.confirm_and_execute();
@@ -312,6 +467,7 @@ fn handle_key_event(
                     app.request_empty_trash();
                 }
             }
+            crate::app::Tab::Stats => {}
         },
         KeyCode::Char('p') if !app.show_search => {
             app.open_settings();
@@ -322,6 +478,8 @@ fn handle_key_event(
                 crate::app::SortField::Project => "project",
                 crate::app::SortField::Name => "name",
                 crate::app::SortField::Messages => "messages",
+                crate::app::SortField::Tokens => "tokens",
+                crate::app::SortField::Cost => "cost",
                 crate::app::SortField::Date => "date",
             };
             app.set_status(format!("Sorted by: {}", sort_name));
@@ -334,6 +492,16 @@ fn handle_key_event(
             };
             app.set_status(format!("Sort direction: {}", dir_name));
         }
+        KeyCode::Char('x') if !app.show_search && app.current_tab == Tab::Sessions => {
+            app.request_secret_scan();
+        }
+        KeyCode::Char('[') if !app.show_search => app.cycle_branch(false),
+        KeyCode::Char(']') if !app.show_search => app.cycle_branch(true),
+        KeyCode::Char('o') if !app.show_search => {
+            app.toggle_tool_output();
+            let state = if app.show_tool_output { "expanded" } else { "collapsed" };
+            app.set_status(format!("Tool output {}", state));
+        }
         KeyCode::Char('h') if !app.show_search => {
             app.toggle_help();
         }
@@ -341,7 +509,10 @@ fn handle_key_event(
             KeyCode::Char(c) => app.add_search_char(c),
             KeyCode::Backspace => app.pop_search_char(),
             KeyCode::Esc => app.show_search = false,
-            KeyCode::Enter => app.show_search = false,
+            KeyCode::Enter => {
+                app.run_full_text_search();
+                app.show_search = false;
+            }
             _ => {}
         },
         _ => {}
@@ -349,13 +520,50 @@ fn handle_key_event(
     None
 }
 
+/// Exportiert die ausgewählte Session und meldet das Ergebnis in der Statuszeile.
+/// Massenexporte laufen in run_app, damit der Fortschritt gezeichnet werden kann.
+fn export_selected(app: &mut App, format: ExportFormat) {
+    if app.export_scope != app::ExportScope::Session {
+        app.pending_bulk_export = Some(format);
+        return;
+    }
+    if let Some(session) = app.get_selected_session() {
+        let export_dir = app.config.resolved_export_path();
+        let session_clone = session.clone();
+        match commands::export_session(&session_clone, &export_dir, format, &app.config) {
+            Ok(outcome) => app.set_status(outcome.describe()),
+            // z.B. fehlerhaftes Template: Ursache anzeigen
+            Err(e) => app.set_status(format!("Export failed: {:#}", e)),
+        }
+    }
+}
+
+/// Exportiert alle Sessions des gewählten Umfangs in einen Projektbaum mit Index.
+/// `redraw` wird vor jeder Session mit aktualisierter Statuszeile aufgerufen.
+fn bulk_export(app: &mut App, format: ExportFormat, mut redraw: impl FnMut(&mut App)) {
+    let sessions = app.export_scope_sessions();
+    let export_dir = app.config.resolved_export_path();
+    let config = app.config.clone();
+    let progress = |done: usize, total: usize, session: &models::Session| {
+        app.set_status(format!("Exporting {}/{}: {}", done + 1, total, session.display_name()));
+        redraw(app);
+    };
+    let result = commands::export_sessions(&sessions, &export_dir, format, &config, progress);
+    match result {
+        Ok(bulk) => app.set_status(bulk.describe()),
+        Err(e) => app.set_status(format!("Export failed: {:#}", e)),
+    }
+}
+
 /// Gibt `true` zurück wenn die App beendet werden soll.
 fn handle_mouse_event(app: &mut App, mouse: event::MouseEvent) -> bool {
     match mouse.kind {
         MouseEventKind::ScrollUp => {
             if app.show_help {
                 app.help_scroll_up(3);
-            } else if !app.show_settings {
+            } else if app.show_secret_scan {
+                app.secret_scan_prev();
+            } else if !app.show_settings && app.conflict.is_none() {
                 // Scroll folgt Mausposition statt Fokus-Panel
                 let list_width = app.terminal_size.0 * 30 / 100;
                 if mouse.column < list_width {
@@ -368,7 +576,9 @@ fn handle_mouse_event(app: &mut App, mouse: event::MouseEvent) -> bool {
         MouseEventKind::ScrollDown => {
             if app.show_help {
                 app.help_scroll_down(3);
-            } else if !app.show_settings {
+            } else if app.show_secret_scan {
+                app.secret_scan_next();
+            } else if !app.show_settings && app.conflict.is_none() {
                 let list_width = app.terminal_size.0 * 30 / 100;
                 if mouse.column < list_width {
                     app.select_next();
@@ -383,6 +593,10 @@ fn handle_mouse_event(app: &mut App, mouse: event::MouseEvent) -> bool {
                 return dispatch_click_action(app, action);
             }
             // Click outside registrierter Region: Modal schließen
+            if app.conflict.is_some() {
+                app.cancel_conflict();
+                return false;
+            }
             if app.show_help {
                 app.toggle_help();
                 return false;
@@ -391,6 +605,14 @@ fn handle_mouse_event(app: &mut App, mouse: event::MouseEvent) -> bool {
                 app.cancel_settings();
                 return false;
             }
+            if app.show_export_picker {
+                app.close_export_picker();
+                return false;
+            }
+            if app.show_secret_scan && !app.is_confirmation_pending() {
+                app.close_secret_scan();
+                return false;
+            }
             if app.show_search {
                 app.show_search = false;
                 return false;
@@ -399,8 +621,12 @@ fn handle_mouse_event(app: &mut App, mouse: event::MouseEvent) -> bool {
                 app.cancel_confirmation();
                 return false;
             }
-            // Normal-Modus: Listen-/Preview-Klick
+            // Normal-Modus: Listen-/Preview-Klick, mit Shift Bereich markieren
+            let from = app.selected_session_idx;
             app.handle_list_click(col, row);
+            if mouse.modifiers.contains(KeyModifiers::SHIFT) && app.focus == app::FocusPanel::List {
+                app.extend_marks(from);
+            }
         }
         _ => {}
     }
@@ -438,31 +664,40 @@ fn dispatch_click_action(app: &mut App, action: crate::app::ClickAction) -> bool
                     }
                     ConfirmAction::DeletePermanently(_)
                     | ConfirmAction::EmptyTrash
-                    | ConfirmAction::TrashZeroMessages => {
+                    | ConfirmAction::TrashZeroMessages
+                    | ConfirmAction::ScrubSecrets(_)
+                    | ConfirmAction::TrashWithSecrets(_)
+                    | ConfirmAction::TrashMarked(_)
+                    | ConfirmAction::RestoreMarked(_)
+                    | ConfirmAction::DeleteMarkedPermanently(_) => {
                         app.confirm_and_execute();
                     }
                 }
             }
         }
         ClickAction::ConfirmNo => app.cancel_confirmation(),
+        ClickAction::ExportAs(format) => {
+            app.close_export_picker();
+            export_selected(app, format);
+        }
+        ClickAction::CycleExportScope => app.cycle_export_scope(),
+        ClickAction::ToggleRedaction => app.toggle_redaction(),
+        ClickAction::ResolveConflict(resolution) => app.resolve_conflict(resolution),
         // Normal-Modus-Aktionen: nur wenn kein Modal offen
         _ => {
-            if app.show_settings || app.show_help || app.is_confirmation_pending() {
+            if app.show_settings
+                || app.show_help
+                || app.show_export_picker
+                || app.show_secret_scan
+                || app.conflict.is_some()
+                || app.is_confirmation_pending()
+            {
                 return false;
             }
             match action {
                 ClickAction::ResumeSession => app.switch_to_selected_session(),
                 ClickAction::DeleteSession => app.request_delete_confirmation(),
-                ClickAction::ExportSession => {
-                    if let Some(session) = app.get_selected_session() {
-                        let export_dir = app.config.resolved_export_path();
-                        let session_clone = session.clone();
-                        match commands::export_session(&session_clone, &export_dir) {
-                            Ok(path) => app.set_status(format!("Exported to {}", path)),
-                            Err(_) => app.set_status("Export failed".to_string()),
-                        }
-                    }
-                }
+                ClickAction::ExportSession => app.open_export_picker(),
                 ClickAction::CleanZeroMessages => app.request_trash_zero_messages(),
                 ClickAction::ToggleSearch => app.toggle_search(),
                 ClickAction::ToggleSort => {
@@ -471,6 +706,8 @@ fn dispatch_click_action(app: &mut App, action: crate::app::ClickAction) -> bool
                         crate::app::SortField::Project => "project",
                         crate::app::SortField::Name => "name",
                         crate::app::SortField::Messages => "messages",
+                        crate::app::SortField::Tokens => "tokens",
+                        crate::app::SortField::Cost => "cost",
                         crate::app::SortField::Date => "date",
                     };
                     app.set_status(format!("Sorted by: {}", sort_name));
@@ -493,7 +730,7 @@ mod tests {
     use super::*;
     use crossterm::event::{KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
     use crate::app::{FocusPanel, Tab};
-    use crate::models::{Message, Session};
+    use crate::models::Session;
     use ratatui::backend::TestBackend;
     use ratatui::Terminal;
 
@@ -513,12 +750,14 @@ mod tests {
             updated_at: String::new(),
             size: 0,
             total_entries: 1,
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: "msg".to_string(),
-            }],
-            jsonl_path: std::path::PathBuf::new(),
+            message_count: 1,
+            first_prompt: Some("msg".to_string()),
+            started_at: None,
+            ended_at: None,
+            token_usage: Default::default(),
+            jsonl_path: std::path::PathBuf::from(format!("/home/g/{}/{}.jsonl", project, id)),
             slug: None,
+            trashed: None,
         }
     }
 
@@ -592,6 +831,13 @@ mod tests {
         assert_eq!(app.current_tab, crate::app::Tab::Trash);
     }
 
+    #[test]
+    fn test_handle_3_switches_to_stats() {
+        let mut app = App::with_sessions(vec![]);
+        handle_key_event(&mut app, press(KeyCode::Char('3')));
+        assert_eq!(app.current_tab, crate::app::Tab::Stats);
+    }
+
     fn mouse(kind: MouseEventKind, col: u16, row: u16) -> event::MouseEvent {
         event::MouseEvent {
             kind,
@@ -652,13 +898,22 @@ mod tests {
     #[test]
     fn test_mouse_click_tab_bar_help_opens_help() {
         let mut app = App::with_sessions(vec![]);
-        // Help-Hint: "│  h help  " = 11 Zeichen → cols 39-49
+        // Help-Hint: "│  h help  " = 11 Zeichen → cols 52-62
         render_frame(&mut app, 160, 40);
         assert!(!app.show_help);
-        handle_mouse_event(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), 42, 1));
+        handle_mouse_event(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), 55, 1));
         assert!(app.show_help);
     }
 
+    #[test]
+    fn test_mouse_click_tab_bar_switches_to_stats() {
+        let mut app = App::with_sessions(vec![]);
+        // Stats-Tab: "  ○ 3 Stats  " = 13 Zeichen → cols 39-51
+        render_frame(&mut app, 160, 40);
+        handle_mouse_event(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), 45, 1));
+        assert_eq!(app.current_tab, crate::app::Tab::Stats);
+    }
+
     #[test]
     fn test_mouse_click_tab_bar_switches_to_sessions() {
         let mut app = App::with_sessions(vec![]);
@@ -1067,15 +1322,66 @@ mod tests {
         assert!(!app.show_rename, "rename should not open in Trash tab");
     }
 
-    // --- 'u' restore from trash ---
+    // --- 'r' restore from trash, 'u' / Ctrl+R undo and redo ---
 
     #[test]
-    fn test_handle_u_restores_from_trash() {
-        let mut app = App::with_sessions(vec![]);
-        app.trash = vec![make_session("t1", "p1")];
+    fn test_handle_r_restores_from_trash() {
+        let (_tmp, mut app) = App::on_disk(vec![], vec![make_session("t1", "p1")]);
+        app.current_tab = Tab::Trash;
+        handle_key_event(&mut app, press(KeyCode::Char('r')));
+        assert!(app.trash.is_empty());
+    }
+
+    #[test]
+    fn test_handle_u_and_ctrl_r_undo_and_redo() {
+        let (_tmp, mut app) = App::on_disk(vec![], vec![make_session("t1", "p1")]);
         app.current_tab = Tab::Trash;
+        handle_key_event(&mut app, press(KeyCode::Char('r')));
         handle_key_event(&mut app, press(KeyCode::Char('u')));
+        assert_eq!(app.trash.len(), 1, "Wiederherstellen rückgängig gemacht");
+
+        let ctrl_r = KeyEvent {
+            modifiers: KeyModifiers::CONTROL,
+            ..press(KeyCode::Char('r'))
+        };
+        handle_key_event(&mut app, ctrl_r);
         assert!(app.trash.is_empty());
+        assert_eq!(app.sessions.len(), 1);
+    }
+
+    #[test]
+    fn test_space_shift_and_a_mark_sessions() {
+        let (_tmp, mut app) = App::on_disk(
+            vec![
+                make_session("s1", "p1"),
+                make_session("s2", "p2"),
+                make_session("s3", "p3"),
+            ],
+            vec![],
+        );
+        let shift = |code| KeyEvent {
+            modifiers: KeyModifiers::SHIFT,
+            ..press(code)
+        };
+        handle_key_event(&mut app, press(KeyCode::Char(' ')));
+        assert_eq!(app.marked.len(), 1);
+        handle_key_event(&mut app, shift(KeyCode::Down));
+        handle_key_event(&mut app, shift(KeyCode::Down));
+        assert_eq!(app.marked.len(), 3);
+        assert_eq!(app.selected_session_idx, 2, "Shift+↓ bewegt auch die Auswahl");
+
+        // Esc hebt erst die Markierung auf, beendet aber nicht
+        assert!(handle_key_event(&mut app, press(KeyCode::Esc)).is_none());
+        assert!(app.marked.is_empty());
+        handle_key_event(&mut app, press(KeyCode::Char('a')));
+        assert_eq!(app.marked_sessions().len(), 3);
+        handle_key_event(&mut app, press(KeyCode::Char('d')));
+        assert!(matches!(
+            app.confirm_action,
+            Some(crate::app::ConfirmAction::TrashMarked(_))
+        ));
+        handle_key_event(&mut app, press(KeyCode::Char('y')));
+        assert_eq!(app.trash.len(), 3, "eine Bestätigung für alle");
     }
 
     // --- 'e' empty trash (Trash tab) ---
@@ -1107,7 +1413,7 @@ mod tests {
     #[test]
     fn test_handle_c_requests_trash_zero() {
         let mut app = App::with_sessions(vec![make_session("s1", "p1")]);
-        app.sessions[0].messages.clear();
+        app.sessions[0].message_count = 0;
         handle_key_event(&mut app, press(KeyCode::Char('c')));
         assert_eq!(
             app.confirm_action,
@@ -1177,15 +1483,152 @@ mod tests {
         assert!(app.resume_session_id.is_some());
     }
 
+    // --- 'o' tool output ---
+
+    #[test]
+    fn test_handle_o_toggles_tool_output() {
+        let mut app = App::with_sessions(vec![make_session("s1", "p1")]);
+        handle_key_event(&mut app, press(KeyCode::Char('o')));
+        assert!(app.show_tool_output);
+        assert!(app.status_message.as_deref().unwrap().contains("expanded"));
+        handle_key_event(&mut app, press(KeyCode::Char('o')));
+        assert!(!app.show_tool_output);
+    }
+
     // --- 'e' export ---
 
+    /// App mit einer Session, deren Transkript in `tmp` liegt; Exporte landen in `tmp/out`.
+    fn exportable_app(tmp: &tempfile::TempDir) -> App {
+        let mut session = make_session("s1", "p1");
+        session.jsonl_path = tmp.path().join("s1.jsonl");
+        std::fs::write(
+            &session.jsonl_path,
+            r#"{"type":"user","message":{"role":"user","content":"msg"},"uuid":"u1"}"#,
+        )
+        .unwrap();
+        let mut app = App::with_sessions(vec![session]);
+        app.config.export_path = tmp.path().join("out").to_string_lossy().to_string();
+        app
+    }
+
     #[test]
     fn test_handle_e_export() {
         let tmp = tempfile::TempDir::new().unwrap();
-        let mut app = App::with_sessions(vec![make_session("s1", "p1")]);
-        app.config.export_path = tmp.path().to_string_lossy().to_string();
+        let mut app = exportable_app(&tmp);
         handle_key_event(&mut app, press(KeyCode::Char('e')));
-        assert!(app.status_message.unwrap().contains("Exported"));
+        assert!(app.show_export_picker, "'e' öffnet den Format-Picker");
+        assert!(app.status_message.is_none());
+        handle_key_event(&mut app, press(KeyCode::Enter));
+        assert!(!app.show_export_picker);
+        let status = app.status_message.unwrap();
+        assert!(status.contains("Exported") && status.ends_with(".md"), "{status}");
+    }
+
+    #[test]
+    fn test_export_picker_arrow_and_digit_selection() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut app = exportable_app(&tmp);
+        handle_key_event(&mut app, press(KeyCode::Char('e')));
+        handle_key_event(&mut app, press(KeyCode::Down));
+        handle_key_event(&mut app, press(KeyCode::Enter));
+        assert!(app.status_message.take().unwrap().ends_with(".html"));
+
+        handle_key_event(&mut app, press(KeyCode::Char('e')));
+        handle_key_event(&mut app, press(KeyCode::Char('5')));
+        assert!(app.status_message.take().unwrap().ends_with(".org"));
+
+        handle_key_event(&mut app, press(KeyCode::Char('e')));
+        handle_key_event(&mut app, press(KeyCode::Esc));
+        assert!(!app.show_export_picker);
+        assert!(app.status_message.is_none(), "Esc exportiert nicht");
+    }
+
+    #[test]
+    fn test_click_export_picker_entry() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut app = exportable_app(&tmp);
+        handle_key_event(&mut app, press(KeyCode::Char('e')));
+        render_frame(&mut app, 120, 40);
+        let rect = app
+            .click_regions
+            .iter()
+            .find(|(_, a)| *a == crate::app::ClickAction::ExportAs(ExportFormat::Text))
+            .map(|(r, _)| *r)
+            .expect("Picker-Einträge müssen klickbar sein");
+        handle_mouse_event(
+            &mut app,
+            mouse(MouseEventKind::Down(MouseButton::Left), rect.x, rect.y),
+        );
+        assert!(!app.show_export_picker);
+        assert!(app.status_message.unwrap().ends_with(".txt"));
+    }
+
+    #[test]
+    fn test_export_picker_bulk_scope() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut app = exportable_app(&tmp);
+        handle_key_event(&mut app, press(KeyCode::Char('e')));
+        handle_key_event(&mut app, press(KeyCode::Tab));
+        handle_key_event(&mut app, press(KeyCode::Enter));
+        assert!(!app.show_export_picker);
+        assert_eq!(app.pending_bulk_export, Some(ExportFormat::Markdown));
+
+        // run_app übernimmt den Export und zeichnet dabei den Fortschritt
+        let format = app.pending_bulk_export.take().unwrap();
+        let mut progress = Vec::new();
+        bulk_export(&mut app, format, |app| progress.push(app.status_message.clone().unwrap()));
+        assert_eq!(progress, vec!["Exporting 1/1: p1 (s1)".to_string()]);
+        assert!(app.status_message.unwrap().starts_with("Exported 1 session —"));
+        assert!(tmp.path().join("out/index.md").exists());
+        assert!(tmp.path().join("out/p1/p1-s1.md").exists());
+    }
+
+    #[test]
+    fn test_export_picker_toggles_redaction() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut app = exportable_app(&tmp);
+        handle_key_event(&mut app, press(KeyCode::Char('e')));
+        handle_key_event(&mut app, press(KeyCode::Char('r')));
+        assert!(app.config.redact_secrets);
+        assert!(app.show_export_picker, "'r' schließt den Picker nicht");
+        handle_key_event(&mut app, press(KeyCode::Enter));
+        let status = app.status_message.unwrap();
+        assert!(status.ends_with("(0 secrets masked)"), "{status}");
+    }
+
+    #[test]
+    fn test_handle_x_secret_scan_modal() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut app = exportable_app(&tmp);
+        std::fs::write(
+            &app.sessions[0].jsonl_path,
+            r#"{"type":"user","message":{"role":"user","content":"SECRET_KEY=s3cr3t-v4lue"},"uuid":"u1"}"#,
+        )
+        .unwrap();
+        handle_key_event(&mut app, press(KeyCode::Char('x')));
+        assert!(app.pending_secret_scan && !app.show_secret_scan);
+
+        // run_app übernimmt den Scan und zeichnet dabei den Fortschritt
+        let mut progress = Vec::new();
+        app.run_secret_scan(|app| progress.push(app.status_message.clone().unwrap()));
+        assert_eq!(progress.last().map(String::as_str), Some("Scanning for secrets 1/1"));
+        assert!(app.show_secret_scan);
+
+        // Bestätigung abbrechen lässt die Liste offen
+        handle_key_event(&mut app, press(KeyCode::Char('s')));
+        assert!(matches!(app.confirm_action, Some(crate::app::ConfirmAction::ScrubSecrets(_))));
+        handle_key_event(&mut app, press(KeyCode::Char('n')));
+        assert!(app.confirm_action.is_none());
+        assert!(app.show_secret_scan);
+
+        handle_key_event(&mut app, press(KeyCode::Char('s')));
+        handle_key_event(&mut app, press(KeyCode::Char('y')));
+        assert!(!app.show_secret_scan);
+        assert!(app.status_message.take().unwrap().starts_with("Masked 1 secret(s)"));
+
+        handle_key_event(&mut app, press(KeyCode::Char('x')));
+        app.run_secret_scan(|_| {});
+        assert!(!app.show_secret_scan, "nach dem Scrub nichts mehr zu melden");
     }
 
     // ─── Neue Layer-1-Maus-Tests: Command-Bar Click-Aktionen ──────────────────
@@ -1234,7 +1677,7 @@ mod tests {
             .find(|(_, a)| *a == crate::app::ClickAction::ToggleSort)
             .map(|(r, _)| *r);
         let rect = region.expect("ToggleSort-Region muss registriert sein");
-        let initial = app.sort_field.clone();
+        let initial = app.sort_field;
         handle_mouse_event(
             &mut app,
             mouse(MouseEventKind::Down(MouseButton::Left), rect.x, rect.y),
@@ -1357,7 +1800,7 @@ mod tests {
 
     #[test]
     fn test_click_command_bar_restore_in_trash_tab_restores_session() {
-        let mut app = App::with_sessions(vec![make_session("s1", "p1")]);
+        let (_tmp, mut app) = App::on_disk(vec![make_session("s1", "p1")], vec![]);
         // Session in Trash verschieben
         app.move_selected_to_trash();
         assert_eq!(app.trash.len(), 1);
@@ -1382,6 +1825,31 @@ mod tests {
         assert_eq!(app.sessions.len(), 1, "Session muss nach Maus-Restore zurück sein");
     }
 
+    #[test]
+    fn test_conflict_modal_captures_keys() {
+        let mut app = App::with_sessions(vec![make_session("s1", "p1")]);
+        let conflict = crate::app::PendingConflict {
+            session_id: "gone".to_string(),
+            operation: crate::app::ConflictOperation::Restore,
+            existing: std::path::PathBuf::from("/p1/gone.jsonl"),
+        };
+        app.conflict = Some(conflict.clone());
+
+        // Andere Tasten wirken nicht, solange das Modal offen ist
+        handle_key_event(&mut app, press(KeyCode::Char('d')));
+        assert!(!app.is_confirmation_pending());
+        assert!(app.conflict.is_some());
+
+        handle_key_event(&mut app, press(KeyCode::Esc));
+        assert!(app.conflict.is_none());
+        assert_eq!(app.status_message.as_deref(), Some("Action cancelled"));
+
+        // Eine Option schließt das Modal; die Session ist hier schon weg
+        app.conflict = Some(conflict);
+        handle_key_event(&mut app, press(KeyCode::Char('m')));
+        assert!(app.conflict.is_none());
+    }
+
     #[test]
     fn test_mouse_click_preview_area_sets_focus_to_preview() {
         let mut app = App::with_sessions(vec![make_session("s1", "p1")]);
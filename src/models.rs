@@ -1,5 +1,7 @@
+use anyhow::Result;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::sync::LazyLock;
 
@@ -21,6 +23,11 @@ static RE_ANSI: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"(\x1b\[[0-9;]*[mGKHFABCDsuJr]|\[[0-9;]*m)").unwrap());
 static RE_BLANK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\n{3,}").unwrap());
 
+/// Maximum length (in chars) of the first prompt kept in the session summary.
+const FIRST_PROMPT_MAX_CHARS: usize = 200;
+
+/// Lightweight summary of a session. The conversation itself is not kept in memory;
+/// use `load_messages` (or the app's transcript cache) to read it on demand.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
     pub id: String,
@@ -30,17 +37,168 @@ pub struct Session {
     pub updated_at: String,
     pub size: u64,
     pub total_entries: usize,
-    pub messages: Vec<Message>,
+    /// Messages on the active branch, i.e. what the preview and exports show.
+    pub message_count: usize,
+    pub first_prompt: Option<String>,
+    /// Timestamps of the first and last message (RFC 3339), independent of file times.
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    /// Token totals per model, from the `usage` of assistant entries.
+    pub token_usage: BTreeMap<String, TokenUsage>,
     #[serde(skip)]
     pub jsonl_path: PathBuf,
     #[serde(skip)]
     pub slug: Option<String>,
+    /// Set for sessions in the trash that were moved there with a sidecar.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trashed: Option<TrashInfo>,
+}
+
+/// Why a session was moved to the trash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TrashReason {
+    /// Deleted on its own, from the list or with `trash` on the command line.
+    Manual,
+    /// Removed by the cleanup of sessions without messages.
+    ZeroMessages,
+    /// Part of an action on several sessions at once.
+    Bulk,
+    /// Trashed from the secret scanner.
+    SecretScan,
+}
+
+impl TrashReason {
+    pub fn label(self) -> &'static str {
+        match self {
+            TrashReason::Manual => "manual",
+            TrashReason::ZeroMessages => "zero-message cleanup",
+            TrashReason::Bulk => "bulk",
+            TrashReason::SecretScan => "secret scan",
+        }
+    }
+}
+
+/// Where a trashed session came from, recorded when it is moved to the trash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrashInfo {
+    /// RFC 3339 timestamp of the move.
+    pub deleted_at: String,
+    /// Absolute path of the JSONL file before it was trashed.
+    pub original_path: PathBuf,
+    /// Display name at the time of deletion.
+    pub title: String,
+    pub reason: TrashReason,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub role: String,
+    /// Cleaned text of all text parts; used for search and the session summary.
     pub content: String,
+    #[serde(default)]
+    pub parts: Vec<MessagePart>,
+    /// RFC 3339 timestamp of the JSONL entry.
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub uuid: Option<String>,
+    #[serde(default)]
+    pub parent_uuid: Option<String>,
+}
+
+/// Token counts as reported in `message.usage` of an assistant entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input: u64,
+    pub output: u64,
+    pub cache_creation: u64,
+    pub cache_read: u64,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u64 {
+        self.input + self.output + self.cache_creation + self.cache_read
+    }
+
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input += other.input;
+        self.output += other.output;
+        self.cache_creation += other.cache_creation;
+        self.cache_read += other.cache_read;
+    }
+
+    fn from_json(usage: &serde_json::Value) -> Self {
+        let field = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+        Self {
+            input: field("input_tokens"),
+            output: field("output_tokens"),
+            cache_creation: field("cache_creation_input_tokens"),
+            cache_read: field("cache_read_input_tokens"),
+        }
+    }
+}
+
+/// One content block of a message, in the order it appeared in the JSONL entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MessagePart {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        is_error: bool,
+        output: String,
+    },
+    Thinking {
+        text: String,
+    },
+}
+
+/// Input fields that best describe a tool call in one line, in order of preference.
+const TOOL_INPUT_KEYS: &[&str] = &[
+    "command",
+    "file_path",
+    "path",
+    "pattern",
+    "url",
+    "query",
+    "description",
+    "prompt",
+];
+
+impl Message {
+    /// True for entries that only carry tool results (sent with role "user" by Claude Code).
+    pub fn is_tool_output(&self) -> bool {
+        !self.parts.is_empty()
+            && self
+                .parts
+                .iter()
+                .all(|p| matches!(p, MessagePart::ToolResult { .. }))
+    }
+}
+
+impl MessagePart {
+    /// One-line description of a tool call's input, e.g. the bash command or file path.
+    pub fn tool_input_summary(input: &serde_json::Value) -> String {
+        if let Some(obj) = input.as_object() {
+            for key in TOOL_INPUT_KEYS {
+                if let Some(value) = obj.get(*key).and_then(|v| v.as_str()) {
+                    return value.lines().next().unwrap_or("").to_string();
+                }
+            }
+            if obj.is_empty() {
+                return String::new();
+            }
+        }
+        input.to_string()
+    }
 }
 
 impl Session {
@@ -59,9 +217,14 @@ impl Session {
             updated_at: chrono::Local::now().to_rfc3339(),
             size: 0,
             total_entries: 0,
-            messages: Vec::new(),
+            message_count: 0,
+            first_prompt: None,
+            started_at: None,
+            ended_at: None,
+            token_usage: BTreeMap::new(),
             jsonl_path: PathBuf::new(),
             slug: None,
+            trashed: None,
         }
     }
 
@@ -81,22 +244,161 @@ impl Session {
             format!("{} ({})", self.project_name, short_id)
         }
     }
+
+    /// Date shown and sorted by in the list: when the session was trashed,
+    /// or its last update if there is no record of that.
+    pub fn list_date(&self) -> &str {
+        self.trashed
+            .as_ref()
+            .map_or(&self.updated_at, |info| &info.deleted_at)
+    }
+
+    /// Sum of all tokens over all models.
+    pub fn total_tokens(&self) -> u64 {
+        self.token_usage.values().map(TokenUsage::total).sum()
+    }
+
+    /// Time between the first and last message, if both carry timestamps.
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        let start = chrono::DateTime::parse_from_rfc3339(self.started_at.as_deref()?).ok()?;
+        let end = chrono::DateTime::parse_from_rfc3339(self.ended_at.as_deref()?).ok()?;
+        Some(end - start)
+    }
+
+    /// Reads and parses the full conversation from the session's JSONL file.
+    pub fn load_messages(&self) -> Result<Vec<Message>> {
+        let content = std::fs::read_to_string(&self.jsonl_path)?;
+        Ok(parse_jsonl_messages(&content))
+    }
+}
+
+/// Timestamps of the first and last message that carry one.
+pub fn message_time_range(messages: &[Message]) -> (Option<String>, Option<String>) {
+    let mut stamps = messages.iter().filter_map(|m| m.timestamp.as_ref());
+    let first = stamps.next().cloned();
+    let last = stamps.next_back().cloned().or_else(|| first.clone());
+    (first, last)
+}
+
+/// Formats an RFC 3339 timestamp in local time ("2026-01-15 10:03:12").
+/// Unparseable input is returned unchanged.
+pub fn format_timestamp(ts: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(ts)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|_| ts.to_string())
+}
+
+/// Compact duration like "2h 05m", "3m 12s" or "45s".
+pub fn format_duration(d: chrono::Duration) -> String {
+    let secs = d.num_seconds().max(0);
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{}h {:02}m", h, m)
+    } else if m > 0 {
+        format!("{}m {:02}s", m, s)
+    } else {
+        format!("{}s", s)
+    }
+}
+
+/// First user message, shortened for the summary.
+pub fn first_user_prompt(messages: &[Message]) -> Option<String> {
+    let msg = messages
+        .iter()
+        .find(|m| m.role == "user" && !m.content.trim().is_empty())?;
+    let text = msg.content.trim();
+    if text.chars().count() > FIRST_PROMPT_MAX_CHARS {
+        let cut: String = text.chars().take(FIRST_PROMPT_MAX_CHARS).collect();
+        Some(format!("{}…", cut))
+    } else {
+        Some(text.to_string())
+    }
 }
 
 /// Extrahiert den customTitle aus JSONL-Inhalt (manueller Rename via Claude Code /rename).
+/// Der letzte Eintrag gilt; ein leerer Titel entfernt den vorherigen.
 pub fn extract_custom_title(content: &str) -> Option<String> {
+    let mut custom_title = None;
     for line in content.lines() {
         if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
             if json.get("type").and_then(|t| t.as_str()) == Some("custom-title") {
                 if let Some(title) = json.get("customTitle").and_then(|s| s.as_str()) {
-                    if !title.is_empty() {
-                        return Some(title.to_string());
-                    }
+                    custom_title = Some(title.to_string()).filter(|t| !t.is_empty());
                 }
             }
         }
     }
-    None
+    custom_title
+}
+
+/// Sums token usage per model over all assistant entries.
+///
+/// Claude Code writes one entry per content block of a response, each repeating the
+/// same `usage`; entries are therefore de-duplicated by `message.id` (last one wins).
+pub fn parse_token_usage(content: &str) -> BTreeMap<String, TokenUsage> {
+    let mut by_message: HashMap<String, (String, TokenUsage)> = HashMap::new();
+    let mut anonymous: Vec<(String, TokenUsage)> = Vec::new();
+
+    for line in content.lines() {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        if json.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+            continue;
+        }
+        let Some(msg) = json.get("message") else {
+            continue;
+        };
+        let (Some(model), Some(usage)) = (
+            msg.get("model").and_then(|m| m.as_str()),
+            msg.get("usage"),
+        ) else {
+            continue;
+        };
+        let entry = (model.to_string(), TokenUsage::from_json(usage));
+        match msg.get("id").and_then(|id| id.as_str()) {
+            Some(id) => {
+                by_message.insert(id.to_string(), entry);
+            }
+            None => anonymous.push(entry),
+        }
+    }
+
+    let mut totals: BTreeMap<String, TokenUsage> = BTreeMap::new();
+    for (model, usage) in by_message.into_values().chain(anonymous) {
+        if usage.total() > 0 {
+            totals.entry(model).or_default().add(&usage);
+        }
+    }
+    totals
+}
+
+/// Compact token count like "850", "12.3k" or "1.2M".
+pub fn format_tokens(tokens: u64) -> String {
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    } else if tokens >= 1_000 {
+        format!("{:.1}k", tokens as f64 / 1_000.0)
+    } else {
+        tokens.to_string()
+    }
+}
+
+/// File size with binary units, like "512 B", "12.3 KB" or "1.2 GB".
+pub fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else if bytes < 1024 * 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+    }
 }
 
 pub fn count_jsonl_entries(content: &str) -> usize {
@@ -179,63 +481,166 @@ fn extract_xml_inner<'a>(text: &'a str, tag: &str) -> Option<&'a str> {
     Some(text[start..end].trim())
 }
 
-pub fn parse_jsonl_messages(content: &str) -> Vec<Message> {
-    let mut messages = Vec::new();
+/// Cleans a text block and keeps it unless it is empty or pure noise.
+fn push_text_part(parts: &mut Vec<MessagePart>, raw: &str) {
+    let text = clean_message_content(raw);
+    if !text.is_empty() && !is_noise_message(&text) {
+        parts.push(MessagePart::Text { text });
+    }
+}
 
-    for line in content.lines() {
-        let json: serde_json::Value = match serde_json::from_str(line) {
-            Ok(v) => v,
-            Err(_) => continue,
-        };
+/// Tool results carry either a plain string or a list of blocks (text, image).
+fn tool_result_output(content: Option<&serde_json::Value>) -> String {
+    let text = match content {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .map(|item| match item.get("type").and_then(|t| t.as_str()) {
+                Some("text") => item
+                    .get("text")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                Some(other) => format!("[{}]", other),
+                None => String::new(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    };
+    strip_ansi(&text).trim_end().to_string()
+}
 
-        let entry_type = json.get("type").and_then(|t| t.as_str()).unwrap_or("");
+/// Turns one user/assistant JSONL entry into a message (without tree links).
+/// Returns `None` for other entry types and for entries without visible content.
+fn parse_entry(json: &serde_json::Value) -> Option<Message> {
+    let entry_type = json.get("type").and_then(|t| t.as_str()).unwrap_or("");
 
-        if entry_type != "user" && entry_type != "assistant" {
-            continue;
-        }
+    if entry_type != "user" && entry_type != "assistant" {
+        return None;
+    }
 
-        let msg = match json.get("message") {
-            Some(m) => m,
-            None => continue,
-        };
+    let msg = json.get("message")?;
 
-        let role = msg
-            .get("role")
-            .and_then(|r| r.as_str())
-            .unwrap_or("unknown");
+    let role = msg
+        .get("role")
+        .and_then(|r| r.as_str())
+        .unwrap_or("unknown");
 
-        let content_val = match msg.get("content") {
-            Some(c) => c,
-            None => continue,
-        };
+    let content_val = msg.get("content")?;
 
-        let text = if let Some(s) = content_val.as_str() {
-            s.to_string()
-        } else if let Some(arr) = content_val.as_array() {
-            arr.iter()
-                .filter_map(|item| {
-                    if item.get("type").and_then(|t| t.as_str()) == Some("text") {
-                        item.get("text").and_then(|t| t.as_str()).map(String::from)
-                    } else {
-                        None
+    let mut parts = Vec::new();
+    if let Some(s) = content_val.as_str() {
+        push_text_part(&mut parts, s);
+    } else if let Some(arr) = content_val.as_array() {
+        for item in arr {
+            let str_field = |key: &str| {
+                item.get(key)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string()
+            };
+            match item.get("type").and_then(|t| t.as_str()) {
+                Some("text") => push_text_part(&mut parts, &str_field("text")),
+                Some("tool_use") => parts.push(MessagePart::ToolUse {
+                    id: str_field("id"),
+                    name: str_field("name"),
+                    input: item.get("input").cloned().unwrap_or_default(),
+                }),
+                Some("tool_result") => parts.push(MessagePart::ToolResult {
+                    tool_use_id: str_field("tool_use_id"),
+                    is_error: item
+                        .get("is_error")
+                        .and_then(|e| e.as_bool())
+                        .unwrap_or(false),
+                    output: tool_result_output(item.get("content")),
+                }),
+                Some("thinking") => {
+                    let text = str_field("thinking");
+                    if !text.trim().is_empty() {
+                        parts.push(MessagePart::Thinking {
+                            text: text.trim().to_string(),
+                        });
                     }
-                })
-                .collect::<Vec<_>>()
-                .join("\n")
-        } else {
-            continue;
+                }
+                _ => {}
+            }
+        }
+    } else {
+        return None;
+    }
+
+    if parts.is_empty() {
+        return None;
+    }
+
+    let content = parts
+        .iter()
+        .filter_map(|p| match p {
+            MessagePart::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let str_field = |key: &str| json.get(key).and_then(|v| v.as_str()).map(String::from);
+    Some(Message {
+        role: role.to_string(),
+        content,
+        parts,
+        timestamp: str_field("timestamp"),
+        uuid: str_field("uuid"),
+        parent_uuid: None,
+    })
+}
+
+/// Parses all messages and links each one to its parent message.
+///
+/// Parent links skip filtered entries (progress, noise, system lines), so
+/// `parent_uuid` always names another returned message or `None` for a root.
+/// Entries without a `parentUuid` field are chained to the previous message.
+pub fn parse_jsonl_messages(content: &str) -> Vec<Message> {
+    let mut messages: Vec<Message> = Vec::new();
+    // uuid eines beliebigen Eintrags → nächste sichtbare Nachricht (er selbst oder ein Vorfahre)
+    let mut nearest_visible: HashMap<String, Option<String>> = HashMap::new();
+    let mut last_visible: Option<String> = None;
+
+    for line in content.lines() {
+        let json: serde_json::Value = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(_) => continue,
         };
 
-        let text = clean_message_content(&text);
+        let uuid = json.get("uuid").and_then(|u| u.as_str());
+        let parent_field = json.get("parentUuid");
+        // Nach /compact ist parentUuid null, der logische Vorgänger steht in logicalParentUuid
+        let raw_parent = parent_field
+            .and_then(|p| p.as_str())
+            .or_else(|| json.get("logicalParentUuid").and_then(|p| p.as_str()));
+        let parent = match (parent_field, raw_parent) {
+            (None, _) => last_visible.clone(),
+            (_, Some(p)) => match nearest_visible.get(p) {
+                Some(visible) => visible.clone(),
+                None => last_visible.clone(),
+            },
+            (Some(_), None) => None,
+        };
 
-        if text.is_empty() || is_noise_message(&text) {
-            continue;
+        match parse_entry(&json) {
+            Some(mut message) => {
+                message.parent_uuid = parent;
+                if let Some(uuid) = uuid {
+                    nearest_visible.insert(uuid.to_string(), Some(uuid.to_string()));
+                    last_visible = Some(uuid.to_string());
+                }
+                messages.push(message);
+            }
+            None => {
+                if let Some(uuid) = uuid {
+                    nearest_visible.insert(uuid.to_string(), parent);
+                }
+            }
         }
-
-        messages.push(Message {
-            role: role.to_string(),
-            content: text,
-        });
     }
 
     messages
@@ -250,7 +655,8 @@ mod tests {
         let session = Session::new("test-id".to_string(), "/home/g/my-project".to_string());
         assert_eq!(session.id, "test-id");
         assert_eq!(session.project_name, "my-project");
-        assert_eq!(session.messages.len(), 0);
+        assert_eq!(session.message_count, 0);
+        assert!(session.first_prompt.is_none());
         assert_eq!(session.total_entries, 0);
     }
 
@@ -295,6 +701,16 @@ mod tests {
         assert_eq!(extract_custom_title(content), None);
     }
 
+    #[test]
+    fn test_extract_custom_title_last_rename_wins() {
+        let content = r#"{"type":"custom-title","customTitle":"first","sessionId":"abc"}
+{"type":"custom-title","customTitle":"second","sessionId":"abc"}
+"#;
+        assert_eq!(extract_custom_title(content), Some("second".to_string()));
+        let cleared = format!("{}{}\n", content, r#"{"type":"custom-title","customTitle":""}"#);
+        assert_eq!(extract_custom_title(&cleared), None);
+    }
+
     #[test]
     fn test_parse_jsonl_user_message_string_content() {
         let line =
@@ -457,4 +873,226 @@ mod tests {
         assert_eq!(messages.len(), 1);
         assert_eq!(messages[0].content, "hello");
     }
+
+    #[test]
+    fn test_first_user_prompt_skips_assistant_and_truncates() {
+        let msgs = vec![
+            Message {
+                role: "assistant".to_string(),
+                content: "greeting".to_string(),
+                parts: Vec::new(),
+                timestamp: None,
+                uuid: None,
+                parent_uuid: None,
+            },
+            Message {
+                role: "user".to_string(),
+                content: format!("  {}  ", "x".repeat(300)),
+                parts: Vec::new(),
+                timestamp: None,
+                uuid: None,
+                parent_uuid: None,
+            },
+        ];
+        let prompt = first_user_prompt(&msgs).unwrap();
+        assert_eq!(prompt.chars().count(), FIRST_PROMPT_MAX_CHARS + 1);
+        assert!(prompt.starts_with('x') && prompt.ends_with('…'));
+        assert!(first_user_prompt(&msgs[..1]).is_none());
+    }
+
+    #[test]
+    fn test_load_messages_reads_jsonl_path() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("s.jsonl");
+        std::fs::write(
+            &path,
+            r#"{"type":"user","message":{"role":"user","content":"from disk"},"uuid":"a"}"#,
+        )
+        .unwrap();
+        let mut session = Session::new("s".to_string(), "/p".to_string());
+        session.jsonl_path = path;
+        let messages = session.load_messages().unwrap();
+        assert_eq!(messages[0].content, "from disk");
+
+        session.jsonl_path = tmp.path().join("missing.jsonl");
+        assert!(session.load_messages().is_err());
+    }
+
+    #[test]
+    fn test_parses_tool_use_result_and_thinking_parts() {
+        let content = concat!(
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"  check files "},{"type":"text","text":"Let me look."},{"type":"tool_use","id":"t1","name":"Read","input":{"file_path":"/src/main.rs"}}]},"uuid":"a"}"#,
+            "\n",
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","is_error":true,"content":[{"type":"text","text":"\u001b[31mnot found\u001b[0m"},{"type":"image"}]}]},"uuid":"b"}"#,
+        );
+        let messages = parse_jsonl_messages(content);
+        assert_eq!(messages.len(), 2);
+
+        assert_eq!(messages[0].content, "Let me look.");
+        assert_eq!(
+            messages[0].parts,
+            vec![
+                MessagePart::Thinking {
+                    text: "check files".to_string()
+                },
+                MessagePart::Text {
+                    text: "Let me look.".to_string()
+                },
+                MessagePart::ToolUse {
+                    id: "t1".to_string(),
+                    name: "Read".to_string(),
+                    input: serde_json::json!({"file_path": "/src/main.rs"}),
+                },
+            ]
+        );
+        assert!(!messages[0].is_tool_output());
+
+        assert!(messages[1].is_tool_output());
+        assert_eq!(messages[1].content, "");
+        assert_eq!(
+            messages[1].parts,
+            vec![MessagePart::ToolResult {
+                tool_use_id: "t1".to_string(),
+                is_error: true,
+                output: "not found\n[image]".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_first_user_prompt_skips_tool_results() {
+        let content = concat!(
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"ok"}]},"uuid":"a"}"#,
+            "\n",
+            r#"{"type":"user","message":{"role":"user","content":"real question"},"uuid":"b"}"#,
+        );
+        let messages = parse_jsonl_messages(content);
+        assert_eq!(first_user_prompt(&messages).as_deref(), Some("real question"));
+    }
+
+    #[test]
+    fn test_tool_input_summary_prefers_known_keys() {
+        let summary = |v: serde_json::Value| MessagePart::tool_input_summary(&v);
+        assert_eq!(summary(serde_json::json!({"command": "cargo test\n--lib"})), "cargo test");
+        assert_eq!(summary(serde_json::json!({"file_path": "/a.rs", "content": "x"})), "/a.rs");
+        assert_eq!(summary(serde_json::json!({})), "");
+        assert_eq!(summary(serde_json::json!({"n": 1})), r#"{"n":1}"#);
+    }
+
+    #[test]
+    fn test_parses_timestamp_uuid_and_parent() {
+        let content = concat!(
+            r#"{"type":"user","message":{"role":"user","content":"hi"},"uuid":"u1","parentUuid":null,"timestamp":"2026-01-15T10:00:00.000Z"}"#,
+            "\n",
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"hello"}]},"uuid":"a1","parentUuid":"u1","timestamp":"2026-01-15T10:02:30.500Z"}"#,
+        );
+        let messages = parse_jsonl_messages(content);
+        assert_eq!(messages[0].uuid.as_deref(), Some("u1"));
+        assert_eq!(messages[0].parent_uuid, None);
+        assert_eq!(messages[1].parent_uuid.as_deref(), Some("u1"));
+        assert_eq!(
+            messages[1].timestamp.as_deref(),
+            Some("2026-01-15T10:02:30.500Z")
+        );
+
+        let (start, end) = message_time_range(&messages);
+        let mut session = Session::new("s".to_string(), "/p".to_string());
+        session.started_at = start;
+        session.ended_at = end;
+        assert_eq!(session.duration().unwrap().num_seconds(), 150);
+    }
+
+    #[test]
+    fn test_message_time_range_single_and_missing() {
+        let mut messages = parse_jsonl_messages(
+            r#"{"type":"user","message":{"role":"user","content":"hi"},"uuid":"u1","timestamp":"2026-01-15T10:00:00Z"}"#,
+        );
+        let (start, end) = message_time_range(&messages);
+        assert_eq!(start, end);
+        messages[0].timestamp = None;
+        assert_eq!(message_time_range(&messages), (None, None));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(chrono::Duration::seconds(45)), "45s");
+        assert_eq!(format_duration(chrono::Duration::seconds(192)), "3m 12s");
+        assert_eq!(format_duration(chrono::Duration::seconds(7500)), "2h 05m");
+        assert_eq!(format_duration(chrono::Duration::seconds(-5)), "0s");
+    }
+
+    #[test]
+    fn test_format_timestamp_falls_back_to_input() {
+        assert_eq!(format_timestamp("not a date"), "not a date");
+        let formatted = format_timestamp("2026-01-15T10:00:00Z");
+        assert_eq!(formatted.len(), "2026-01-15 10:00:00".len());
+    }
+
+    #[test]
+    fn test_parent_links_skip_filtered_entries() {
+        let content = concat!(
+            r#"{"type":"user","message":{"role":"user","content":"question"},"uuid":"u1","parentUuid":null}"#,
+            "\n",
+            r#"{"type":"progress","data":{},"uuid":"p1","parentUuid":"u1"}"#,
+            "\n",
+            r#"{"type":"user","message":{"role":"user","content":"<command-name>/model</command-name><command-args></command-args>"},"uuid":"n1","parentUuid":"p1"}"#,
+            "\n",
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"answer"}]},"uuid":"a1","parentUuid":"n1"}"#,
+            "\n",
+            r#"{"type":"system","subtype":"compact_boundary","uuid":"s1","parentUuid":null,"logicalParentUuid":"a1"}"#,
+            "\n",
+            r#"{"type":"user","message":{"role":"user","content":"after compact"},"uuid":"u2","parentUuid":"s1"}"#,
+        );
+        let messages = parse_jsonl_messages(content);
+        let links: Vec<(Option<&str>, Option<&str>)> = messages
+            .iter()
+            .map(|m| (m.uuid.as_deref(), m.parent_uuid.as_deref()))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                (Some("u1"), None),
+                (Some("a1"), Some("u1")),
+                (Some("u2"), Some("a1")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_token_usage_dedupes_by_message_id() {
+        let content = concat!(
+            r#"{"type":"assistant","message":{"id":"m1","model":"claude-opus-4","role":"assistant","content":[{"type":"thinking","thinking":"x"}],"usage":{"input_tokens":10,"output_tokens":5,"cache_creation_input_tokens":100,"cache_read_input_tokens":1000}}}"#,
+            "\n",
+            r#"{"type":"assistant","message":{"id":"m1","model":"claude-opus-4","role":"assistant","content":[{"type":"text","text":"y"}],"usage":{"input_tokens":10,"output_tokens":20,"cache_creation_input_tokens":100,"cache_read_input_tokens":1000}}}"#,
+            "\n",
+            r#"{"type":"assistant","message":{"id":"m2","model":"claude-haiku-4","role":"assistant","content":"z","usage":{"input_tokens":3,"output_tokens":4}}}"#,
+            "\n",
+            r#"{"type":"assistant","message":{"id":"m3","model":"<synthetic>","role":"assistant","content":"err","usage":{"input_tokens":0,"output_tokens":0}}}"#,
+            "\n",
+            r#"{"type":"user","message":{"role":"user","content":"hi","usage":{"input_tokens":99}}}"#,
+        );
+        let usage = parse_token_usage(content);
+        assert_eq!(usage.len(), 2, "zero-usage models are dropped");
+        assert_eq!(
+            usage["claude-opus-4"],
+            TokenUsage {
+                input: 10,
+                output: 20,
+                cache_creation: 100,
+                cache_read: 1000
+            }
+        );
+        assert_eq!(usage["claude-haiku-4"].total(), 7);
+
+        let mut session = Session::new("s".to_string(), "/p".to_string());
+        session.token_usage = usage;
+        assert_eq!(session.total_tokens(), 1137);
+    }
+
+    #[test]
+    fn test_format_tokens() {
+        assert_eq!(format_tokens(850), "850");
+        assert_eq!(format_tokens(12_345), "12.3k");
+        assert_eq!(format_tokens(1_250_000), "1.2M");
+    }
 }
@@ -0,0 +1,184 @@
+use crate::models::TokenUsage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Prices in USD per million tokens.
+///
+/// Cache prices are optional; without them the usual multipliers of the input
+/// price apply (1.25× for cache writes, 0.1× for cache reads).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read: Option<f64>,
+}
+
+impl ModelPricing {
+    const fn new(input: f64, output: f64) -> Self {
+        Self {
+            input,
+            output,
+            cache_write: None,
+            cache_read: None,
+        }
+    }
+
+    pub fn cache_write(&self) -> f64 {
+        self.cache_write.unwrap_or(self.input * 1.25)
+    }
+
+    pub fn cache_read(&self) -> f64 {
+        self.cache_read.unwrap_or(self.input * 0.1)
+    }
+
+    /// Cost of `usage` in USD.
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        (usage.input as f64 * self.input
+            + usage.output as f64 * self.output
+            + usage.cache_creation as f64 * self.cache_write()
+            + usage.cache_read as f64 * self.cache_read())
+            / 1_000_000.0
+    }
+}
+
+/// Built-in list prices, keyed by model-name prefix.
+const DEFAULT_PRICING: &[(&str, ModelPricing)] = &[
+    ("claude-opus-4-5", ModelPricing::new(5.0, 25.0)),
+    ("claude-opus-4", ModelPricing::new(15.0, 75.0)),
+    ("claude-3-opus", ModelPricing::new(15.0, 75.0)),
+    ("claude-sonnet-4", ModelPricing::new(3.0, 15.0)),
+    ("claude-3-7-sonnet", ModelPricing::new(3.0, 15.0)),
+    ("claude-3-5-sonnet", ModelPricing::new(3.0, 15.0)),
+    ("claude-haiku-4-5", ModelPricing::new(1.0, 5.0)),
+    ("claude-3-5-haiku", ModelPricing::new(0.8, 4.0)),
+    ("claude-3-haiku", ModelPricing::new(0.25, 1.25)),
+];
+
+/// Pricing table stored in the config. Only the user's overrides are persisted;
+/// keys are model names or prefixes, and the longest matching key wins.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Pricing {
+    overrides: BTreeMap<String, ModelPricing>,
+}
+
+impl Pricing {
+    #[cfg(test)]
+    pub fn with_override(mut self, model: &str, pricing: ModelPricing) -> Self {
+        self.overrides.insert(model.to_string(), pricing);
+        self
+    }
+
+    /// Price for `model`: user overrides first, then the built-in defaults.
+    pub fn for_model(&self, model: &str) -> Option<ModelPricing> {
+        longest_prefix(
+            self.overrides
+                .iter()
+                .map(|(prefix, pricing)| (prefix.as_str(), pricing)),
+            model,
+        )
+        .or_else(|| longest_prefix(DEFAULT_PRICING.iter().map(|(p, m)| (*p, m)), model))
+    }
+
+    /// Estimated cost of a session's per-model usage. Models without a known
+    /// price are not counted.
+    pub fn cost(&self, usage: &BTreeMap<String, TokenUsage>) -> f64 {
+        usage
+            .iter()
+            .filter_map(|(model, usage)| self.for_model(model).map(|p| p.cost(usage)))
+            .sum()
+    }
+}
+
+fn longest_prefix<'a>(
+    table: impl Iterator<Item = (&'a str, &'a ModelPricing)>,
+    model: &str,
+) -> Option<ModelPricing> {
+    table
+        .filter(|(prefix, _)| model.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, pricing)| *pricing)
+}
+
+/// Formats a USD amount: "$0.00", "<$0.01", "$12.34".
+pub fn format_cost(usd: f64) -> String {
+    if usd <= 0.0 {
+        // Also covers -0.0, the result of summing no values
+        "$0.00".to_string()
+    } else if usd < 0.01 {
+        "<$0.01".to_string()
+    } else {
+        format!("${:.2}", usd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(input: u64, output: u64, cache_creation: u64, cache_read: u64) -> TokenUsage {
+        TokenUsage {
+            input,
+            output,
+            cache_creation,
+            cache_read,
+        }
+    }
+
+    #[test]
+    fn test_default_pricing_uses_longest_prefix() {
+        let pricing = Pricing::default();
+        assert_eq!(pricing.for_model("claude-opus-4-1-20250805").unwrap().input, 15.0);
+        assert_eq!(pricing.for_model("claude-opus-4-5-20251101").unwrap().input, 5.0);
+        assert_eq!(pricing.for_model("claude-sonnet-4-20250514").unwrap().output, 15.0);
+        assert_eq!(pricing.for_model("<synthetic>"), None);
+    }
+
+    #[test]
+    fn test_cost_applies_cache_multipliers() {
+        let sonnet = ModelPricing::new(3.0, 15.0);
+        // 1M in + 1M out + 1M cache write (3.75) + 1M cache read (0.30)
+        let cost = sonnet.cost(&usage(1_000_000, 1_000_000, 1_000_000, 1_000_000));
+        assert!((cost - 22.05).abs() < 1e-9, "{cost}");
+    }
+
+    #[test]
+    fn test_overrides_win_and_can_set_cache_prices() {
+        let pricing = Pricing::default().with_override(
+            "claude-sonnet-4",
+            ModelPricing {
+                input: 2.0,
+                output: 10.0,
+                cache_write: None,
+                cache_read: Some(0.0),
+            },
+        );
+        let mut per_model = BTreeMap::new();
+        per_model.insert("claude-sonnet-4-20250514".to_string(), usage(1_000_000, 0, 0, 5_000_000));
+        per_model.insert("unknown-model".to_string(), usage(1_000_000, 1_000_000, 0, 0));
+        assert!((pricing.cost(&per_model) - 2.0).abs() < 1e-9);
+        // Other models keep their defaults
+        assert_eq!(pricing.for_model("claude-opus-4").unwrap().input, 15.0);
+    }
+
+    #[test]
+    fn test_overrides_roundtrip_as_plain_map() {
+        let json = r#"{"claude-opus-4":{"input":10.0,"output":50.0,"cache_read":1.0}}"#;
+        let pricing: Pricing = serde_json::from_str(json).unwrap();
+        let opus = pricing.for_model("claude-opus-4-1").unwrap();
+        assert_eq!(opus.cache_read(), 1.0);
+        assert_eq!(opus.cache_write(), 12.5);
+        assert_eq!(serde_json::to_string(&pricing).unwrap(), json);
+    }
+
+    #[test]
+    fn test_format_cost() {
+        assert_eq!(format_cost(0.0), "$0.00");
+        assert_eq!(format_cost(std::iter::empty::<f64>().sum()), "$0.00");
+        assert_eq!(format_cost(0.004), "<$0.01");
+        assert_eq!(format_cost(12.346), "$12.35");
+    }
+}
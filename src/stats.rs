@@ -0,0 +1,226 @@
+use crate::models::Session;
+use crate::pricing::Pricing;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Timelike};
+use std::collections::HashMap;
+
+/// Days shown in the daily activity chart.
+pub const ACTIVITY_DAYS: usize = 30;
+/// Weeks shown in the weekly activity chart.
+pub const ACTIVITY_WEEKS: usize = 12;
+/// Entries in the "largest sessions" list.
+const LARGEST_SESSIONS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectStats {
+    pub name: String,
+    pub sessions: usize,
+    pub messages: usize,
+    pub size: u64,
+}
+
+/// Totals across all sessions for the Stats tab. Activity is counted by the
+/// day a session started, in local time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    pub sessions: usize,
+    pub messages: usize,
+    pub tokens: u64,
+    pub size: u64,
+    /// Estimated cost in USD.
+    pub cost: f64,
+    /// Sorted by session count, most active project first.
+    pub projects: Vec<ProjectStats>,
+    /// Sessions per day, oldest first; the last entry is `today`.
+    pub daily: Vec<u64>,
+    /// Sessions per ISO week (Monday to Sunday), oldest first; the last entry is the current week.
+    pub weekly: Vec<u64>,
+    /// Sessions started per hour of the day.
+    pub hours: [u64; 24],
+    /// (display name, size), largest first.
+    pub largest: Vec<(String, u64)>,
+}
+
+impl Stats {
+    pub fn compute(sessions: &[Session], pricing: &Pricing, today: NaiveDate) -> Self {
+        let mut stats = Stats {
+            daily: vec![0; ACTIVITY_DAYS],
+            weekly: vec![0; ACTIVITY_WEEKS],
+            ..Default::default()
+        };
+        let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+        let mut projects: HashMap<&str, ProjectStats> = HashMap::new();
+
+        for session in sessions {
+            stats.sessions += 1;
+            stats.messages += session.message_count;
+            stats.tokens += session.total_tokens();
+            stats.size += session.size;
+            stats.cost += pricing.cost(&session.token_usage);
+
+            let project = projects
+                .entry(session.display_project_name())
+                .or_insert_with(|| ProjectStats {
+                    name: session.display_project_name().to_string(),
+                    sessions: 0,
+                    messages: 0,
+                    size: 0,
+                });
+            project.sessions += 1;
+            project.messages += session.message_count;
+            project.size += session.size;
+
+            let Some(started) = start_time(session) else {
+                continue;
+            };
+            stats.hours[started.hour() as usize] += 1;
+            let date = started.date_naive();
+            if let Some(slot) = bucket(today, date, 1, ACTIVITY_DAYS) {
+                stats.daily[slot] += 1;
+            }
+            if let Some(slot) = bucket(this_monday, date, 7, ACTIVITY_WEEKS) {
+                stats.weekly[slot] += 1;
+            }
+        }
+
+        stats.projects = projects.into_values().collect();
+        stats
+            .projects
+            .sort_by(|a, b| b.sessions.cmp(&a.sessions).then_with(|| a.name.cmp(&b.name)));
+
+        let mut by_size: Vec<&Session> = sessions.iter().collect();
+        by_size.sort_by_key(|s| std::cmp::Reverse(s.size));
+        stats.largest = by_size
+            .into_iter()
+            .take(LARGEST_SESSIONS)
+            .map(|s| (s.display_name(), s.size))
+            .collect();
+        stats
+    }
+
+    /// Hour with the most session starts, if any session has a known start time.
+    pub fn busiest_hour(&self) -> Option<usize> {
+        let (hour, count) = self
+            .hours
+            .iter()
+            .enumerate()
+            .max_by(|(ha, a), (hb, b)| a.cmp(b).then(hb.cmp(ha)))?;
+        (*count > 0).then_some(hour)
+    }
+}
+
+/// First message timestamp, falling back to the file creation time.
+fn start_time(session: &Session) -> Option<DateTime<Local>> {
+    session
+        .started_at
+        .as_deref()
+        .into_iter()
+        .chain(std::iter::once(session.created_at.as_str()))
+        .find_map(|ts| DateTime::parse_from_rfc3339(ts).ok())
+        .map(|dt| dt.with_timezone(&Local))
+}
+
+/// Slot of `date` in a chart of `slots` buckets of `days` days each, where the
+/// last bucket starts at `last_start`. Dates in the future or too far back yield `None`.
+fn bucket(last_start: NaiveDate, date: NaiveDate, days: i64, slots: usize) -> Option<usize> {
+    let before = (last_start - date).num_days();
+    if before <= -days {
+        return None;
+    }
+    let age = usize::try_from((before.max(0) + days - 1) / days).ok()?;
+    (age < slots).then(|| slots - 1 - age)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn session(id: &str, project: &str, started: Option<DateTime<Local>>, size: u64) -> Session {
+        let mut s = Session::new(id.to_string(), format!("/home/g/{project}"));
+        s.project_name = project.to_string();
+        s.started_at = started.map(|t| t.to_rfc3339());
+        s.message_count = 2;
+        s.size = size;
+        s
+    }
+
+    fn at(date: NaiveDate, hour: u32) -> DateTime<Local> {
+        Local
+            .from_local_datetime(&date.and_hms_opt(hour, 30, 0).unwrap())
+            .earliest()
+            .unwrap()
+    }
+
+    // Wednesday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, 11).unwrap()
+    }
+
+    #[test]
+    fn test_totals_and_projects() {
+        let mut a = session("a", "alpha", None, 100);
+        a.token_usage.insert(
+            "claude-sonnet-4".to_string(),
+            crate::models::TokenUsage {
+                input: 1_000_000,
+                ..Default::default()
+            },
+        );
+        let stats = Stats::compute(
+            &[
+                a,
+                session("b", "beta", None, 50),
+                session("c", "beta", None, 10),
+            ],
+            &Pricing::default(),
+            today(),
+        );
+        assert_eq!(stats.sessions, 3);
+        assert_eq!(stats.messages, 6);
+        assert_eq!(stats.size, 160);
+        assert_eq!(stats.cost, 3.0);
+        assert_eq!(stats.projects[0].name, "beta");
+        assert_eq!(stats.projects[0].sessions, 2);
+        assert_eq!(stats.projects[0].size, 60);
+        assert_eq!(stats.projects[1].name, "alpha");
+        assert_eq!(stats.largest[0].1, 100);
+        assert_eq!(stats.largest.len(), 3);
+    }
+
+    #[test]
+    fn test_activity_buckets() {
+        let t = today();
+        let stats = Stats::compute(
+            &[
+                session("a", "p", Some(at(t, 9)), 1),
+                session("b", "p", Some(at(t - Duration::days(1), 9)), 1),
+                // Monday of this week
+                session("c", "p", Some(at(t - Duration::days(2), 14)), 1),
+                // Sunday of last week
+                session("d", "p", Some(at(t - Duration::days(3), 14)), 1),
+                // Outside both charts
+                session("e", "p", Some(at(t - Duration::days(200), 14)), 1),
+            ],
+            &Pricing::default(),
+            t,
+        );
+        assert_eq!(stats.daily[ACTIVITY_DAYS - 1], 1);
+        assert_eq!(stats.daily[ACTIVITY_DAYS - 2], 1);
+        assert_eq!(stats.daily[ACTIVITY_DAYS - 4], 1);
+        assert_eq!(stats.daily.iter().sum::<u64>(), 4);
+        assert_eq!(stats.weekly[ACTIVITY_WEEKS - 1], 3);
+        assert_eq!(stats.weekly[ACTIVITY_WEEKS - 2], 1);
+        assert_eq!(stats.hours[9], 2);
+        assert_eq!(stats.hours[14], 3);
+        assert_eq!(stats.busiest_hour(), Some(14));
+    }
+
+    #[test]
+    fn test_empty_stats() {
+        let stats = Stats::compute(&[], &Pricing::default(), today());
+        assert_eq!(stats.daily.len(), ACTIVITY_DAYS);
+        assert_eq!(stats.weekly.len(), ACTIVITY_WEEKS);
+        assert_eq!(stats.busiest_hour(), None);
+        assert!(stats.projects.is_empty());
+    }
+}
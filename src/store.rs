@@ -1,8 +1,106 @@
-use crate::models::{extract_custom_title, parse_jsonl_messages, Session};
-use anyhow::{Context, Result};
+use crate::config::{AppConfig, TrashRetention};
+use crate::index::{self, IndexEntry, SessionIndex};
+use crate::models::{
+    count_jsonl_entries, extract_custom_title, first_user_prompt, format_size,
+    message_time_range, parse_jsonl_messages, parse_token_usage, Session, TrashInfo, TrashReason,
+};
+use crate::tree::ConversationTree;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local};
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Minimum interval between two progress callbacks while sessions are loading.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Snapshot of the loading state reported by `load_sessions_with_progress`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LoadProgress {
+    /// Files processed so far (parsed or failed).
+    pub loaded: usize,
+    pub total: usize,
+    /// Sum of the sizes of all files actually read and parsed (not served from the index).
+    pub bytes: u64,
+    /// Files that could not be read or parsed.
+    pub failed: usize,
+}
+
+/// Bytes used on disk by the projects and trash directories.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DiskUsage {
+    pub projects: u64,
+    pub trash: u64,
+}
+
+/// Sessions deleted from the trash by `SessionStore::purge_trash`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TrashPurge {
+    /// IDs of the purged sessions, oldest first.
+    pub purged: Vec<String>,
+    /// How many of them exceeded the age limit; the rest were over the size limit.
+    pub expired: usize,
+    pub freed_bytes: u64,
+    /// Sessions due for purging whose files could not be deleted.
+    pub failed: Vec<PurgeFailure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PurgeFailure {
+    pub id: String,
+    pub error: String,
+}
+
+impl TrashPurge {
+    /// Status line like "Purged 3 sessions from the trash (2 expired, 1 over
+    /// the size limit), 1.2 MB freed", plus how many could not be deleted.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.purged.is_empty() {
+            let over_size = self.purged.len() - self.expired;
+            let reasons = match (self.expired, over_size) {
+                (expired, 0) => format!("{} expired", expired),
+                (0, over_size) => format!("{} over the size limit", over_size),
+                (expired, over_size) => {
+                    format!("{} expired, {} over the size limit", expired, over_size)
+                }
+            };
+            parts.push(format!(
+                "Purged {} session(s) from the trash ({}), {} freed",
+                self.purged.len(),
+                reasons,
+                format_size(self.freed_bytes)
+            ));
+        }
+        if !self.failed.is_empty() {
+            parts.push(format!("{} session(s) could not be purged", self.failed.len()));
+        }
+        parts.join("; ")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.purged.is_empty() && self.failed.is_empty()
+    }
+}
+
+/// Total size of all files below `path` (0 if it does not exist). Symlinks are not followed.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
 
 /// Checks whether `candidate` matches a child directory of `parent`.
 /// First tries an exact name match, then a fuzzy match where dots in the real
@@ -32,6 +130,14 @@ fn match_child_dir(parent: &Path, candidate: &str) -> Option<PathBuf> {
     None
 }
 
+/// Converts a project directory into the slug Claude Code uses for it: every
+/// character other than an ASCII letter or digit becomes `-`.
+pub fn path_to_slug(path: &str) -> String {
+    path.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
 /// Converts a Claude project slug (e.g. "-home-g-agent-session-manager") back to
 /// the actual filesystem path (e.g. "/home/g/agent-session-manager").
 /// On Windows, also handles drive-letter slugs (e.g. "C--Users-foo" → "C:\Users\foo").
@@ -96,9 +202,252 @@ fn slug_to_path(slug: &str) -> Option<PathBuf> {
     Some(path)
 }
 
+/// Lists every `*.jsonl` file one level below `root` as
+/// (file path, project slug, resolved project path).
+fn collect_session_files(root: &Path) -> Result<Vec<(PathBuf, String, String)>> {
+    let mut paths = Vec::new();
+    for project_entry in fs::read_dir(root)? {
+        let project_entry = project_entry?;
+        let project_path = project_entry.path();
+
+        if !project_path.is_dir() {
+            continue;
+        }
+
+        let project_slug = project_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let resolved_path = slug_to_path(&project_slug)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| project_slug.clone());
+
+        for file_entry in fs::read_dir(&project_path)? {
+            let file_entry = file_entry?;
+            let file_path = file_entry.path();
+
+            if file_path.extension().and_then(|e| e.to_str()) == Some("jsonl") {
+                paths.push((file_path, project_slug.clone(), resolved_path.clone()));
+            }
+        }
+    }
+    Ok(paths)
+}
+
+/// Sidecar next to a trashed JSONL file that records where it came from
+/// (`<id>.jsonl` → `<id>.trash.json`).
+fn trash_info_path(jsonl_path: &Path) -> PathBuf {
+    jsonl_path.with_extension("trash.json")
+}
+
+/// Reads the sidecar of a trashed file; `None` if it is missing or unreadable,
+/// e.g. for files trashed by older versions.
+fn read_trash_info(jsonl_path: &Path) -> Option<TrashInfo> {
+    let content = fs::read_to_string(trash_info_path(jsonl_path)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Removes a trashed JSONL file, its sidecar and, once empty, its project directory.
+fn remove_trash_file(path: &Path) -> Result<()> {
+    fs::remove_file(path)?;
+    let _ = fs::remove_file(trash_info_path(path));
+    if let Some(dir) = path.parent() {
+        // Only succeeds once the project directory is empty
+        let _ = fs::remove_dir(dir);
+    }
+    Ok(())
+}
+
+/// What to do when a session or export file already exists. Shared by moving
+/// sessions to and from the trash, importing bundles and exporting; each of them
+/// rejects the policies it cannot apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictPolicy {
+    /// Fail and leave both files alone
+    Abort,
+    /// Keep the existing file and leave the new one out
+    Skip,
+    /// Replace the existing file
+    Overwrite,
+    /// Keep both; the new file gets a counter suffix, a moved session a new ID
+    #[serde(alias = "suffix")]
+    KeepBoth,
+    /// Combine both transcripts, keeping every entry once
+    Merge,
+}
+
+impl ConflictPolicy {
+    /// The choices of the conflict modal, in the order shown.
+    pub const RESOLUTIONS: [ConflictPolicy; 3] = [
+        ConflictPolicy::KeepBoth,
+        ConflictPolicy::Overwrite,
+        ConflictPolicy::Merge,
+    ];
+
+    /// What an export may do when the target file exists; transcripts are not
+    /// merged into rendered files.
+    pub const EXPORTS: [ConflictPolicy; 4] = [
+        ConflictPolicy::Overwrite,
+        ConflictPolicy::KeepBoth,
+        ConflictPolicy::Skip,
+        ConflictPolicy::Abort,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ConflictPolicy::Abort => "Abort",
+            ConflictPolicy::Skip => "Skip (keep existing)",
+            ConflictPolicy::KeepBoth => "Keep both (new ID)",
+            ConflictPolicy::Overwrite => "Overwrite existing",
+            ConflictPolicy::Merge => "Merge transcripts",
+        }
+    }
+}
+
+/// Writes via a temporary file in the same directory, so readers never see a
+/// half-written transcript.
+fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let dir = path.parent().context("session path has no parent")?;
+    fs::create_dir_all(dir)?;
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("session");
+    let tmp = dir.join(format!(".{}.tmp", name));
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Moves `src` to `dst`. If `dst` exists, `on_conflict` decides; `Abort` and
+/// `Skip` refuse. Returns where the file ended up, which differs from `dst` for
+/// `KeepBoth`.
+fn move_session_file(src: &Path, dst: &Path, on_conflict: ConflictPolicy) -> Result<PathBuf> {
+    let dir = dst.parent().context("session path has no parent")?;
+    fs::create_dir_all(dir)?;
+    if !dst.exists() {
+        fs::rename(src, dst)?;
+        return Ok(dst.to_path_buf());
+    }
+    match on_conflict {
+        ConflictPolicy::Abort | ConflictPolicy::Skip => bail!("{} already exists", dst.display()),
+        ConflictPolicy::Overwrite => {
+            fs::rename(src, dst)?;
+            Ok(dst.to_path_buf())
+        }
+        ConflictPolicy::Merge => {
+            let merged = merge_jsonl(&fs::read_to_string(dst)?, &fs::read_to_string(src)?);
+            write_atomic(dst, &merged)?;
+            fs::remove_file(src)?;
+            Ok(dst.to_path_buf())
+        }
+        ConflictPolicy::KeepBoth => {
+            let id = src.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+            let new_id = fresh_session_id(id, |candidate| {
+                dir.join(format!("{}.jsonl", candidate)).exists()
+            });
+            let path = dir.join(format!("{}.jsonl", new_id));
+            write_atomic(&path, &with_session_id(&fs::read_to_string(src)?, id, &new_id))?;
+            fs::remove_file(src)?;
+            Ok(path)
+        }
+    }
+}
+
+/// Entries of `existing` and those of `incoming` it does not have yet (same
+/// `uuid`, or the same line for entries without one), in timestamp order.
+/// Lines without a timestamp stay behind the entry they followed.
+pub fn merge_jsonl(existing: &str, incoming: &str) -> String {
+    let parse = |line: &str| serde_json::from_str::<serde_json::Value>(line).ok();
+    let uuid = |value: &Option<serde_json::Value>| {
+        value.as_ref()?.get("uuid")?.as_str().map(str::to_string)
+    };
+    let known: std::collections::HashSet<String> = existing
+        .lines()
+        .map(|line| uuid(&parse(line)).unwrap_or_else(|| line.to_string()))
+        .collect();
+
+    // (sort key, file order, line); the key is the latest timestamp so far in its file
+    let mut entries = Vec::new();
+    for (file, content) in [existing, incoming].into_iter().enumerate() {
+        let mut timestamp = String::new();
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let value = parse(line);
+            if file == 1 && known.contains(&uuid(&value).unwrap_or_else(|| line.to_string())) {
+                continue;
+            }
+            if let Some(ts) = value.as_ref().and_then(|v| v.get("timestamp")?.as_str()) {
+                timestamp = ts.to_string();
+            }
+            entries.push((timestamp.clone(), file, line));
+        }
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+    let mut merged: String = entries.iter().map(|(_, _, line)| format!("{}\n", line)).collect();
+    if merged.is_empty() {
+        merged.push('\n');
+    }
+    merged
+}
+
+/// Sets `sessionId` to `new_id` in every entry that carries `old_id`. Other
+/// lines are kept byte for byte.
+fn with_session_id(content: &str, old_id: &str, new_id: &str) -> String {
+    content
+        .split('\n')
+        .map(|line| match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(mut value) if value.get("sessionId").and_then(|v| v.as_str()) == Some(old_id) => {
+                value["sessionId"] = new_id.into();
+                value.to_string()
+            }
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A new UUID-shaped session ID for the copy of session `id`, derived from the
+/// old ID and the current time; `taken` rejects IDs that are already in use.
+fn fresh_session_id(id: &str, taken: impl Fn(&str) -> bool) -> String {
+    let nanos = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+    (0u32..)
+        .map(|attempt| {
+            let digest = Sha256::digest(format!("{}:{}:{}", id, nanos, attempt));
+            let hex: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+            // Version 4 and RFC 4122 variant, like the IDs Claude Code assigns
+            format!(
+                "{}-{}-4{}-a{}-{}",
+                &hex[..8],
+                &hex[8..12],
+                &hex[13..16],
+                &hex[17..20],
+                &hex[20..32]
+            )
+        })
+        .find(|candidate| candidate != id && !taken(candidate))
+        .expect("unbounded")
+}
+
+/// A session file that was created, modified or removed while the app is running.
+#[derive(Debug)]
+pub enum SessionChange {
+    /// The file exists (new or modified) and was parsed again.
+    Upserted { in_trash: bool, session: Box<Session> },
+    /// The file no longer exists.
+    Removed { in_trash: bool, id: String },
+}
+
 pub struct SessionStore {
     projects_path: PathBuf,
     trash_path: PathBuf,
+    /// Persistent metadata index; `None` disables caching.
+    index_path: Option<PathBuf>,
+}
+
+impl Default for SessionStore {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SessionStore {
@@ -111,6 +460,7 @@ impl SessionStore {
         Self {
             projects_path: base.join("projects"),
             trash_path: base.join("trash"),
+            index_path: Some(AppConfig::config_dir().join("session-index.json")),
         }
     }
 
@@ -119,56 +469,24 @@ impl SessionStore {
         Self {
             projects_path: base.join("projects"),
             trash_path: base.join("trash"),
+            index_path: None,
         }
     }
 
+    #[cfg(test)]
+    pub fn with_index(mut self, index_path: PathBuf) -> Self {
+        self.index_path = Some(index_path);
+        self
+    }
+
     #[allow(dead_code)] // Used in integration tests (tests/integration.rs)
     pub fn load_sessions(&self) -> Result<Vec<Session>> {
-        let mut sessions = Vec::new();
-
-        if !self.projects_path.exists() {
-            return Ok(sessions);
-        }
-
-        for project_entry in fs::read_dir(&self.projects_path)? {
-            let project_entry = project_entry?;
-            let project_path = project_entry.path();
-
-            if !project_path.is_dir() {
-                continue;
-            }
-
-            let project_slug = project_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown")
-                .to_string();
-
-            let resolved_path = slug_to_path(&project_slug)
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|| project_slug.clone());
-
-            for file_entry in fs::read_dir(&project_path)? {
-                let file_entry = file_entry?;
-                let file_path = file_entry.path();
-
-                if file_path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
-                    continue;
-                }
-
-                if let Ok(session) =
-                    self.load_session_from_jsonl(&file_path, &project_slug, &resolved_path)
-                {
-                    sessions.push(session);
-                }
-            }
-        }
-
+        let mut sessions = self.load_sessions_with_progress(|_| {})?;
         sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
-
         Ok(sessions)
     }
 
+    #[allow(dead_code)] // Used in tests
     pub fn count_session_files(&self) -> usize {
         if !self.projects_path.exists() {
             return 0;
@@ -192,67 +510,117 @@ impl SessionStore {
         count
     }
 
+    /// Loads all sessions in parallel and reports progress while the rayon workers run.
+    /// The callback is invoked on the calling thread (at most every `PROGRESS_INTERVAL`),
+    /// so it may safely redraw the terminal. A final call with the complete tally is
+    /// always made before returning.
     pub fn load_sessions_with_progress<F>(&self, mut on_progress: F) -> Result<Vec<Session>>
     where
-        F: FnMut(usize, usize),
+        F: FnMut(&LoadProgress),
     {
         if !self.projects_path.exists() {
             return Ok(Vec::new());
         }
 
-        let total = self.count_session_files();
-
-        let file_paths: Vec<(PathBuf, String, String)> = {
-            let mut paths = Vec::new();
-            for project_entry in fs::read_dir(&self.projects_path)? {
-                let project_entry = project_entry?;
-                let project_path = project_entry.path();
+        let file_paths = collect_session_files(&self.projects_path)?;
+        let index = self.load_index();
+
+        let total = file_paths.len();
+        let loaded = AtomicUsize::new(0);
+        let failed = AtomicUsize::new(0);
+        let bytes = AtomicU64::new(0);
+        let snapshot = || LoadProgress {
+            loaded: loaded.load(Ordering::Relaxed),
+            total,
+            bytes: bytes.load(Ordering::Relaxed),
+            failed: failed.load(Ordering::Relaxed),
+        };
 
-                if !project_path.is_dir() {
-                    continue;
-                }
+        // Workers only bump the shared counters; the calling thread polls them
+        // and forwards throttled snapshots to `on_progress`.
+        let loaded_files = std::thread::scope(|scope| {
+            let worker = scope.spawn(|| {
+                file_paths
+                    .into_par_iter()
+                    .filter_map(|(file_path, project_slug, resolved_path)| {
+                        let result = self.load_session_from_jsonl(
+                            &file_path,
+                            &project_slug,
+                            &resolved_path,
+                            &index,
+                        );
+                        match &result {
+                            // Files served from the index were not read, so they
+                            // don't count towards the parsed bytes.
+                            Ok((session, _, false)) => {
+                                bytes.fetch_add(session.size, Ordering::Relaxed);
+                            }
+                            Ok(_) => {}
+                            Err(_) => {
+                                failed.fetch_add(1, Ordering::Relaxed);
+                            }
+                        }
+                        loaded.fetch_add(1, Ordering::Relaxed);
+                        result.ok().map(|(session, entry, _)| (session, entry))
+                    })
+                    .collect::<Vec<(Session, IndexEntry)>>()
+            });
+
+            while !worker.is_finished() {
+                on_progress(&snapshot());
+                std::thread::sleep(PROGRESS_INTERVAL);
+            }
 
-                let project_slug = project_path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("unknown")
-                    .to_string();
+            worker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        });
 
-                let resolved_path = slug_to_path(&project_slug)
-                    .map(|p| p.to_string_lossy().to_string())
-                    .unwrap_or_else(|| project_slug.clone());
+        on_progress(&snapshot());
 
-                for file_entry in fs::read_dir(&project_path)? {
-                    let file_entry = file_entry?;
-                    let file_path = file_entry.path();
+        Ok(self.update_index(index, &self.projects_path, loaded_files))
+    }
 
-                    if file_path.extension().and_then(|e| e.to_str()) == Some("jsonl") {
-                        paths.push((file_path, project_slug.clone(), resolved_path.clone()));
-                    }
-                }
-            }
-            paths
-        };
+    fn load_index(&self) -> SessionIndex {
+        self.index_path
+            .as_deref()
+            .map(SessionIndex::load)
+            .unwrap_or_default()
+    }
 
-        let sessions: Vec<Session> = file_paths
-            .into_par_iter()
-            .filter_map(|(file_path, project_slug, resolved_path)| {
-                self.load_session_from_jsonl(&file_path, &project_slug, &resolved_path)
-                    .ok()
+    /// Stores the freshly loaded entries below `root` in the index (saving it if anything
+    /// changed) and returns the sessions.
+    fn update_index(
+        &self,
+        mut index: SessionIndex,
+        root: &Path,
+        loaded: Vec<(Session, IndexEntry)>,
+    ) -> Vec<Session> {
+        let (sessions, entries): (Vec<_>, Vec<_>) = loaded
+            .into_iter()
+            .map(|(session, entry)| {
+                let path = session.jsonl_path.clone();
+                (session, (path, entry))
             })
-            .collect();
-
-        on_progress(total, total);
-
-        Ok(sessions)
+            .unzip();
+        if let Some(path) = &self.index_path {
+            if index.replace_under(root, entries) {
+                let _ = index.save(path);
+            }
+        }
+        sessions
     }
 
+    /// Builds a session from its JSONL file, reusing the parsed fields from `index`
+    /// when the file's mtime and size are unchanged. Returns the entry to store back
+    /// and whether it came from the index, i.e. the file was not read.
     fn load_session_from_jsonl(
         &self,
         path: &Path,
         project_name: &str,
         project_path: &str,
-    ) -> Result<Session> {
+        index: &SessionIndex,
+    ) -> Result<(Session, IndexEntry, bool)> {
         let session_id = path
             .file_stem()
             .and_then(|n| n.to_str())
@@ -261,80 +629,142 @@ impl SessionStore {
 
         let metadata = fs::metadata(path)?;
         let file_size = metadata.len();
+        let mtime_ns = index::mtime_ns(&metadata);
 
         let modified = metadata
             .modified()
             .ok()
-            .and_then(|t| {
+            .map(|t| {
                 let datetime: chrono::DateTime<chrono::Local> = t.into();
-                Some(datetime.to_rfc3339())
+                datetime.to_rfc3339()
             })
             .unwrap_or_default();
 
         let created = metadata
             .created()
             .ok()
-            .and_then(|t| {
+            .map(|t| {
                 let datetime: chrono::DateTime<chrono::Local> = t.into();
-                Some(datetime.to_rfc3339())
+                datetime.to_rfc3339()
             })
             .unwrap_or_default();
 
-        let content = fs::read_to_string(path)?;
-        let total_entries = crate::models::count_jsonl_entries(&content);
-        let messages = parse_jsonl_messages(&content);
-        let slug = extract_custom_title(&content);
+        let cached = index.lookup(path, mtime_ns, file_size);
+        let from_index = cached.is_some();
+        let entry = match cached {
+            Some(cached) => cached.clone(),
+            None => {
+                let content = fs::read_to_string(path)?;
+                let tree = ConversationTree::build(parse_jsonl_messages(&content));
+                let messages = tree.messages();
+                let (started_at, ended_at) = message_time_range(messages);
+                IndexEntry {
+                    mtime_ns,
+                    size: file_size,
+                    total_entries: count_jsonl_entries(&content),
+                    slug: extract_custom_title(&content),
+                    message_count: tree.branch_path(tree.active_branch()).len(),
+                    first_prompt: first_user_prompt(messages),
+                    started_at,
+                    ended_at,
+                    token_usage: parse_token_usage(&content),
+                }
+            }
+        };
 
-        Ok(Session {
+        let session = Session {
             id: session_id,
             project_path: project_path.to_string(),
             project_name: project_name.to_string(),
             created_at: created,
             updated_at: modified,
             size: file_size,
-            total_entries,
-            messages,
+            total_entries: entry.total_entries,
+            message_count: entry.message_count,
+            first_prompt: entry.first_prompt.clone(),
+            started_at: entry.started_at.clone(),
+            ended_at: entry.ended_at.clone(),
+            token_usage: entry.token_usage.clone(),
             jsonl_path: path.to_path_buf(),
-            slug,
-        })
+            slug: entry.slug.clone(),
+            trashed: None,
+        };
+        Ok((session, entry, from_index))
     }
 
     pub fn load_trash(&self) -> Result<Vec<Session>> {
         if !self.trash_path.exists() {
             return Ok(Vec::new());
         }
-        let mut sessions = Vec::new();
-        for project_entry in fs::read_dir(&self.trash_path)? {
-            let project_entry = project_entry?;
-            let project_path = project_entry.path();
-            if !project_path.is_dir() {
-                continue;
-            }
-            let project_slug = project_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown")
-                .to_string();
-            let resolved_path = slug_to_path(&project_slug)
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|| project_slug.clone());
-            for file_entry in fs::read_dir(&project_path)? {
-                let file_entry = file_entry?;
-                let file_path = file_entry.path();
-                if file_path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
-                    continue;
-                }
-                if let Ok(session) =
-                    self.load_session_from_jsonl(&file_path, &project_slug, &resolved_path)
-                {
-                    sessions.push(session);
-                }
-            }
+        let index = self.load_index();
+        let loaded: Vec<(Session, IndexEntry)> = collect_session_files(&self.trash_path)?
+            .into_iter()
+            .filter_map(|(file_path, project_slug, resolved_path)| {
+                self.load_session_from_jsonl(&file_path, &project_slug, &resolved_path, &index)
+                    .ok()
+                    .map(|(session, entry, _)| (session, entry))
+            })
+            .collect();
+        let mut sessions = self.update_index(index, &self.trash_path, loaded);
+        for session in &mut sessions {
+            session.trashed = read_trash_info(&session.jsonl_path);
         }
-        sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        sessions.sort_by(|a, b| b.list_date().cmp(a.list_date()));
         Ok(sessions)
     }
 
+    pub fn projects_path(&self) -> &Path {
+        &self.projects_path
+    }
+
+    pub fn trash_path(&self) -> &Path {
+        &self.trash_path
+    }
+
+    /// Walks both directories, including files that are not sessions (e.g. tool output).
+    pub fn disk_usage(&self) -> DiskUsage {
+        DiskUsage {
+            projects: dir_size(&self.projects_path),
+            trash: dir_size(&self.trash_path),
+        }
+    }
+
+    /// Re-reads a single JSONL file after a filesystem event.
+    /// Returns `None` for paths that are not `<root>/<project>/<id>.jsonl` below the
+    /// projects or trash directory, or for files that cannot be parsed (yet).
+    pub fn reload_file(&self, path: &Path) -> Option<SessionChange> {
+        let (rel, in_trash) = if let Ok(rel) = path.strip_prefix(&self.projects_path) {
+            (rel, false)
+        } else {
+            (path.strip_prefix(&self.trash_path).ok()?, true)
+        };
+        if rel.components().count() != 2
+            || path.extension().and_then(|e| e.to_str()) != Some("jsonl")
+        {
+            return None;
+        }
+        let id = path.file_stem()?.to_str()?.to_string();
+
+        if !path.is_file() {
+            return Some(SessionChange::Removed { in_trash, id });
+        }
+        let project_slug = rel.components().next()?.as_os_str().to_str()?.to_string();
+        let resolved_path = slug_to_path(&project_slug)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| project_slug.clone());
+        // Kein Index: einzelne Dateien sind schnell geparst, der Index wird beim nächsten Start aktualisiert
+        let (mut session, _, _) = self
+            .load_session_from_jsonl(path, &project_slug, &resolved_path, &SessionIndex::default())
+            .ok()?;
+        if in_trash {
+            session.trashed = read_trash_info(path);
+        }
+        Some(SessionChange::Upserted {
+            in_trash,
+            session: Box::new(session),
+        })
+    }
+
     /// Returns the path to a session's JSONL file based on project_name and id
     pub fn get_session_file_path(&self, project_name: &str, session_id: &str) -> PathBuf {
         self.projects_path
@@ -342,26 +772,189 @@ impl SessionStore {
             .join(format!("{}.jsonl", session_id))
     }
 
-    /// Moves a session's JSONL file from projects to trash directory
-    pub fn move_to_trash(&self, project_name: &str, session_id: &str) -> Result<()> {
-        let src = self.get_session_file_path(project_name, session_id);
-        let dst_dir = self.trash_path.join(project_name);
-        fs::create_dir_all(&dst_dir)?;
-        let dst = dst_dir.join(format!("{}.jsonl", session_id));
-        fs::rename(&src, &dst)?;
-        Ok(())
+    /// Finds the JSONL file of session `session_id` in any project directory.
+    pub fn find_session_file(&self, session_id: &str) -> Option<PathBuf> {
+        fs::read_dir(&self.projects_path)
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path().join(format!("{}.jsonl", session_id)))
+            .find(|path| path.is_file())
+    }
+
+    /// Writes a session's JSONL file into `projects/<project_name>`, replacing an
+    /// existing file. Goes through a temporary file so a running Claude Code
+    /// never sees a half-written transcript.
+    pub fn write_session_file(
+        &self,
+        project_name: &str,
+        session_id: &str,
+        content: &str,
+    ) -> Result<PathBuf> {
+        let path = self.get_session_file_path(project_name, session_id);
+        write_atomic(&path, content)?;
+        Ok(path)
+    }
+
+    /// Moves a session's JSONL file from projects to trash directory and
+    /// records when, from where and why in a sidecar next to it. An already
+    /// trashed copy is only replaced as `on_conflict` says. Returns the session
+    /// as it is now in the trash.
+    pub fn move_to_trash(
+        &self,
+        session: &Session,
+        reason: TrashReason,
+        on_conflict: ConflictPolicy,
+    ) -> Result<Session> {
+        let src = self.get_session_file_path(&session.project_name, &session.id);
+        let dst = self.trash_file_path(session);
+        let moved = move_session_file(&src, &dst, on_conflict)?;
+
+        let info = TrashInfo {
+            deleted_at: chrono::Local::now().to_rfc3339(),
+            original_path: std::path::absolute(&src).unwrap_or(src),
+            title: session.display_name(),
+            reason,
+        };
+        // The move already happened; without a sidecar the session is simply
+        // restored to its project directory later.
+        if let Ok(json) = serde_json::to_vec_pretty(&info) {
+            let _ = fs::write(trash_info_path(&moved), json);
+        }
+        self.load_moved(&moved)
+    }
+
+    /// Restores a session's JSONL file from trash to where it was deleted from
+    /// (per its sidecar), or to its project directory if there is no record.
+    /// An existing file there is only replaced as `on_conflict` says. Returns
+    /// the session as it is now in the projects directory.
+    pub fn restore_session_file(
+        &self,
+        session: &Session,
+        on_conflict: ConflictPolicy,
+    ) -> Result<Session> {
+        let src = self.trash_file_path(session);
+        let dst = self.restore_target(session);
+        let moved = move_session_file(&src, &dst, on_conflict)?;
+        let _ = fs::remove_file(trash_info_path(&src));
+        self.load_moved(&moved)
+    }
+
+    /// The trashed file that moving `session` to the trash would collide with.
+    pub fn trash_conflict(&self, session: &Session) -> Option<PathBuf> {
+        Some(self.trash_file_path(session)).filter(|path| path.exists())
+    }
+
+    /// The file that restoring `session` from the trash would collide with.
+    pub fn restore_conflict(&self, session: &Session) -> Option<PathBuf> {
+        Some(self.restore_target(session)).filter(|path| path.exists())
     }
 
-    /// Restores a session's JSONL file from trash back to projects directory
-    pub fn restore_session_file(&self, session: &Session) -> Result<()> {
-        let src = self.trash_path
+    /// Reads a session right after it was moved; the project comes from the
+    /// directory it is in now.
+    fn load_moved(&self, path: &Path) -> Result<Session> {
+        let slug = path
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .and_then(|name| name.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let resolved = slug_to_path(&slug)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| slug.clone());
+        let (mut session, _, _) =
+            self.load_session_from_jsonl(path, &slug, &resolved, &SessionIndex::default())?;
+        session.trashed = read_trash_info(path);
+        Ok(session)
+    }
+
+    fn trash_file_path(&self, session: &Session) -> PathBuf {
+        self.trash_path
             .join(&session.project_name)
-            .join(format!("{}.jsonl", session.id));
-        let dst_dir = self.projects_path.join(&session.project_name);
-        fs::create_dir_all(&dst_dir)?;
-        let dst = dst_dir.join(format!("{}.jsonl", session.id));
-        fs::rename(&src, &dst)?;
-        Ok(())
+            .join(format!("{}.jsonl", session.id))
+    }
+
+    /// The recorded directory, under the session's current ID (which differs from
+    /// the recorded file name after "keep both"). A recorded path that is not a
+    /// project directory of this store (data dir moved, sidecar edited) is ignored.
+    fn restore_target(&self, session: &Session) -> PathBuf {
+        let projects = std::path::absolute(&self.projects_path)
+            .unwrap_or_else(|_| self.projects_path.clone());
+        let recorded_dir = read_trash_info(&self.trash_file_path(session))
+            .map(|info| info.original_path)
+            .filter(|path| !path.components().any(|c| c == Component::ParentDir))
+            .and_then(|path| path.parent().map(Path::to_path_buf))
+            .filter(|dir| dir.parent() == Some(projects.as_path()));
+        match recorded_dir {
+            Some(dir) => dir.join(format!("{}.jsonl", session.id)),
+            None => self.get_session_file_path(&session.project_name, &session.id),
+        }
+    }
+
+    /// Applies `retention` to the trash: first deletes sessions trashed before
+    /// the age limit, then the oldest remaining ones until the trash fits the
+    /// size limit. Sessions without a sidecar count from their file's mtime.
+    pub fn purge_trash(
+        &self,
+        retention: &TrashRetention,
+        now: DateTime<Local>,
+    ) -> Result<TrashPurge> {
+        let mut purge = TrashPurge::default();
+        if !retention.is_enabled() || !self.trash_path.exists() {
+            return Ok(purge);
+        }
+
+        // (deleted at, file, bytes incl. sidecar), oldest first
+        let mut entries: Vec<(DateTime<Local>, PathBuf, u64)> =
+            collect_session_files(&self.trash_path)?
+                .into_iter()
+                .filter_map(|(path, _, _)| {
+                    let meta = fs::metadata(&path).ok()?;
+                    let deleted_at = read_trash_info(&path)
+                        .and_then(|info| DateTime::parse_from_rfc3339(&info.deleted_at).ok())
+                        .map(|t| t.with_timezone(&Local))
+                        .or_else(|| meta.modified().ok().map(DateTime::from))?;
+                    let sidecar = fs::metadata(trash_info_path(&path)).map_or(0, |m| m.len());
+                    Some((deleted_at, path, meta.len() + sidecar))
+                })
+                .collect();
+        entries.sort_by_key(|(deleted_at, _, _)| *deleted_at);
+
+        // Limits too large to represent simply never apply
+        let cutoff = retention
+            .max_age_days
+            .and_then(|days| now.checked_sub_signed(chrono::Duration::days(days.into())));
+        let mut remaining = dir_size(&self.trash_path);
+        let max_bytes = retention.max_size_mb.map(|mb| mb.saturating_mul(1024 * 1024));
+        for (deleted_at, path, bytes) in entries {
+            let expired = cutoff.is_some_and(|cutoff| deleted_at < cutoff);
+            if !expired && max_bytes.is_none_or(|max| remaining <= max) {
+                // Sorted by age: nothing after this is expired either
+                break;
+            }
+            let id = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string();
+            // A file that cannot be deleted still takes space; keep going with the next one
+            if let Err(e) = remove_trash_file(&path) {
+                purge.failed.push(PurgeFailure {
+                    id,
+                    error: format!("{:#}", e),
+                });
+                continue;
+            }
+            remaining = remaining.saturating_sub(bytes);
+            purge.freed_bytes += bytes;
+            purge.expired += usize::from(expired);
+            purge.purged.push(id);
+        }
+        Ok(purge)
+    }
+
+    /// Deletes a trashed session for good: its file and sidecar.
+    pub fn delete_from_trash(&self, session: &Session) -> Result<()> {
+        remove_trash_file(&self.trash_file_path(session))
     }
 
     /// Removes the entire trash directory
@@ -385,6 +978,11 @@ mod tests {
         (tmp, store)
     }
 
+    fn move_to_trash(store: &SessionStore, project: &str, id: &str) -> Result<Session> {
+        let session = Session::new(id.to_string(), project.to_string());
+        store.move_to_trash(&session, TrashReason::Manual, ConflictPolicy::Abort)
+    }
+
     #[test]
     fn test_empty_dir_returns_empty() {
         let (tmp, store) = create_test_store();
@@ -433,6 +1031,23 @@ mod tests {
         assert_eq!(sessions[0].slug, None);
     }
 
+    #[test]
+    fn test_token_usage_survives_index_round_trip() {
+        let (tmp, store) = create_test_store();
+        let store = store.with_index(tmp.path().join("config/session-index.json"));
+
+        let project_dir = tmp.path().join("projects/-home-g-myproject");
+        fs::create_dir_all(&project_dir).unwrap();
+        let jsonl_content = r#"{"type":"assistant","message":{"id":"m1","role":"assistant","model":"claude-opus-4","content":"hi","usage":{"input_tokens":10,"output_tokens":20}},"uuid":"a1"}"#;
+        fs::write(project_dir.join("tok.jsonl"), jsonl_content).unwrap();
+
+        for _ in 0..2 {
+            let sessions = store.load_sessions().unwrap();
+            assert_eq!(sessions[0].total_tokens(), 30);
+            assert_eq!(sessions[0].token_usage["claude-opus-4"].output, 20);
+        }
+    }
+
     #[test]
     fn test_loads_sessions_from_project_subdirs() {
         let (tmp, store) = create_test_store();
@@ -452,11 +1067,15 @@ mod tests {
         assert_eq!(sessions.len(), 1);
         assert_eq!(sessions[0].id, "abc-123");
         assert_eq!(sessions[0].project_name, "-home-g-myproject");
-        assert_eq!(sessions[0].messages.len(), 2);
-        assert_eq!(sessions[0].messages[0].role, "user");
-        assert_eq!(sessions[0].messages[0].content, "hello");
-        assert_eq!(sessions[0].messages[1].role, "assistant");
-        assert_eq!(sessions[0].messages[1].content, "hi there");
+        assert_eq!(sessions[0].message_count, 2);
+        assert_eq!(sessions[0].first_prompt.as_deref(), Some("hello"));
+
+        // Full text is only read on demand
+        let messages = sessions[0].load_messages().unwrap();
+        assert_eq!(messages[0].role, "user");
+        assert_eq!(messages[0].content, "hello");
+        assert_eq!(messages[1].role, "assistant");
+        assert_eq!(messages[1].content, "hi there");
     }
 
     #[test]
@@ -540,14 +1159,182 @@ mod tests {
 
         let mut progress_calls = Vec::new();
         let sessions = store
-            .load_sessions_with_progress(|loaded, total| {
-                progress_calls.push((loaded, total));
+            .load_sessions_with_progress(|progress| {
+                progress_calls.push(*progress);
             })
             .unwrap();
 
         assert_eq!(sessions.len(), 2);
-        assert_eq!(progress_calls.len(), 1);
-        assert_eq!(progress_calls[0], (2, 2));
+        assert!(!progress_calls.is_empty());
+        assert_eq!(
+            *progress_calls.last().unwrap(),
+            LoadProgress {
+                loaded: 2,
+                total: 2,
+                bytes: 2 * line.len() as u64,
+                failed: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_sessions_with_progress_is_monotonic() {
+        let (tmp, store) = create_test_store();
+
+        let p1 = tmp.path().join("projects/project-a");
+        fs::create_dir_all(&p1).unwrap();
+
+        let line = r#"{"type":"user","message":{"role":"user","content":"msg"},"uuid":"x"}"#;
+        for i in 0..50 {
+            fs::write(p1.join(format!("s{}.jsonl", i)), line).unwrap();
+        }
+
+        let mut progress_calls = Vec::new();
+        store
+            .load_sessions_with_progress(|progress| progress_calls.push(*progress))
+            .unwrap();
+
+        assert!(progress_calls.iter().all(|p| p.total == 50));
+        assert!(progress_calls.windows(2).all(|w| w[0].loaded <= w[1].loaded));
+        assert_eq!(progress_calls.last().unwrap().loaded, 50);
+    }
+
+    #[test]
+    fn test_load_sessions_with_progress_counts_failed_files() {
+        let (tmp, store) = create_test_store();
+
+        let p1 = tmp.path().join("projects/project-a");
+        fs::create_dir_all(&p1).unwrap();
+
+        let line = r#"{"type":"user","message":{"role":"user","content":"msg"},"uuid":"x"}"#;
+        fs::write(p1.join("ok.jsonl"), line).unwrap();
+        // Invalid UTF-8 cannot be read as a string and must be reported as failed
+        fs::write(p1.join("broken.jsonl"), [0xff, 0xfe, 0xfd]).unwrap();
+
+        let mut last = LoadProgress::default();
+        let sessions = store
+            .load_sessions_with_progress(|progress| last = *progress)
+            .unwrap();
+
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(last.loaded, 2);
+        assert_eq!(last.failed, 1);
+        assert_eq!(last.bytes, line.len() as u64);
+    }
+
+    #[test]
+    fn test_index_is_written_and_reused() {
+        let (tmp, store) = create_test_store();
+        let index_path = tmp.path().join("config/session-index.json");
+        let store = store.with_index(index_path.clone());
+
+        let project_dir = tmp.path().join("projects/-home-g-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        let line = r#"{"type":"user","message":{"role":"user","content":"hello"},"uuid":"x"}"#;
+        fs::write(project_dir.join("s1.jsonl"), line).unwrap();
+
+        let sessions = store.load_sessions().unwrap();
+        assert_eq!(sessions[0].first_prompt.as_deref(), Some("hello"));
+        assert!(index_path.exists(), "index must be saved after first load");
+
+        // Tamper with the cached text: an unchanged file must be served from the index
+        let cached = fs::read_to_string(&index_path).unwrap();
+        fs::write(&index_path, cached.replace("hello", "HELLO")).unwrap();
+        let sessions = store.load_sessions().unwrap();
+        assert_eq!(sessions[0].first_prompt.as_deref(), Some("HELLO"));
+    }
+
+    #[test]
+    fn test_progress_bytes_skip_files_served_from_index() {
+        let (tmp, store) = create_test_store();
+        let store = store.with_index(tmp.path().join("session-index.json"));
+
+        let project_dir = tmp.path().join("projects/-home-g-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        let line = r#"{"type":"user","message":{"role":"user","content":"hello"},"uuid":"x"}"#;
+        fs::write(project_dir.join("s1.jsonl"), line).unwrap();
+        fs::write(project_dir.join("s2.jsonl"), line).unwrap();
+
+        let mut last = LoadProgress::default();
+        store
+            .load_sessions_with_progress(|progress| last = *progress)
+            .unwrap();
+        assert_eq!(last.bytes, 2 * line.len() as u64);
+
+        fs::write(project_dir.join("s2.jsonl"), format!("{}\n{}", line, line)).unwrap();
+        store
+            .load_sessions_with_progress(|progress| last = *progress)
+            .unwrap();
+        assert_eq!(last.loaded, 2);
+        assert_eq!(last.bytes, 2 * line.len() as u64 + 1, "only the changed file is read");
+    }
+
+    #[test]
+    fn test_index_reparses_changed_files_and_drops_removed() {
+        let (tmp, store) = create_test_store();
+        let index_path = tmp.path().join("session-index.json");
+        let store = store.with_index(index_path.clone());
+
+        let project_dir = tmp.path().join("projects/-home-g-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        let line = r#"{"type":"user","message":{"role":"user","content":"hello"},"uuid":"x"}"#;
+        fs::write(project_dir.join("s1.jsonl"), line).unwrap();
+        fs::write(project_dir.join("s2.jsonl"), line).unwrap();
+        store.load_sessions().unwrap();
+
+        let appended = format!(
+            "{}\n{}",
+            line,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"again"}]},"uuid":"y"}"#
+        );
+        fs::write(project_dir.join("s1.jsonl"), appended).unwrap();
+        fs::remove_file(project_dir.join("s2.jsonl")).unwrap();
+
+        let sessions = store.load_sessions().unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].message_count, 2);
+        let cached = fs::read_to_string(&index_path).unwrap();
+        assert!(!cached.contains("s2.jsonl"), "removed file must be pruned from index");
+    }
+
+    #[test]
+    fn test_index_keeps_trash_entries_separate() {
+        let (tmp, store) = create_test_store();
+        let index_path = tmp.path().join("session-index.json");
+        let store = store.with_index(index_path.clone());
+
+        let project_dir = tmp.path().join("projects/-home-g-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        let line = r#"{"type":"user","message":{"role":"user","content":"hi"},"uuid":"x"}"#;
+        fs::write(project_dir.join("s1.jsonl"), line).unwrap();
+        fs::write(project_dir.join("s2.jsonl"), line).unwrap();
+        move_to_trash(&store, "-home-g-project", "s2").unwrap();
+
+        store.load_sessions().unwrap();
+        store.load_trash().unwrap();
+        store.load_sessions().unwrap();
+
+        let cached = fs::read_to_string(&index_path).unwrap();
+        assert!(cached.contains("s1.jsonl"));
+        assert!(cached.contains("s2.jsonl"), "reloading projects must not drop trash entries");
+    }
+
+    #[test]
+    fn test_write_and_find_session_file() {
+        let (_tmp, store) = create_test_store();
+        assert_eq!(store.find_session_file("s1"), None);
+        let path = store.write_session_file("-p", "s1", "{}\n").unwrap();
+        assert_eq!(path, store.get_session_file_path("-p", "s1"));
+        assert_eq!(store.find_session_file("s1"), Some(path.clone()));
+        store.write_session_file("-p", "s1", "new\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new\n");
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1, "no temp file left");
+    }
+
+    #[test]
+    fn test_path_to_slug() {
+        assert_eq!(path_to_slug("/home/g/my.project"), "-home-g-my-project");
+        assert_eq!(path_to_slug("C:\\Users\\foo"), "C--Users-foo");
     }
 
     #[test]
@@ -618,6 +1405,28 @@ mod tests {
         assert_eq!(result, Some(tmp.path().join("g.strainovic")));
     }
 
+    #[test]
+    fn test_disk_usage_counts_nested_files() {
+        let (tmp, store) = create_test_store();
+        assert_eq!(store.disk_usage(), DiskUsage::default());
+
+        let project_dir = tmp.path().join("projects/-home-g-project");
+        fs::create_dir_all(project_dir.join("s1/tool-results")).unwrap();
+        fs::write(project_dir.join("s1.jsonl"), "x".repeat(100)).unwrap();
+        fs::write(project_dir.join("s1/tool-results/out.txt"), "y".repeat(20)).unwrap();
+        let trash_dir = tmp.path().join("trash/-home-g-project");
+        fs::create_dir_all(&trash_dir).unwrap();
+        fs::write(trash_dir.join("s2.jsonl"), "z".repeat(7)).unwrap();
+
+        assert_eq!(
+            store.disk_usage(),
+            DiskUsage {
+                projects: 120,
+                trash: 7
+            }
+        );
+    }
+
     #[test]
     fn test_move_to_trash_moves_file() {
         let (tmp, store) = create_test_store();
@@ -626,7 +1435,7 @@ mod tests {
         let line = r#"{"type":"user","message":{"role":"user","content":"hi"},"uuid":"x"}"#;
         fs::write(project_dir.join("s1.jsonl"), line).unwrap();
 
-        store.move_to_trash("-home-g-project", "s1").unwrap();
+        move_to_trash(&store, "-home-g-project", "s1").unwrap();
 
         assert!(!project_dir.join("s1.jsonl").exists());
         assert!(tmp.path().join("trash/-home-g-project/s1.jsonl").exists());
@@ -654,12 +1463,367 @@ mod tests {
         let line = r#"{"type":"user","message":{"role":"user","content":"hi"},"uuid":"x"}"#;
         fs::write(project_dir.join("s1.jsonl"), line).unwrap();
 
-        store.move_to_trash("-home-g-project", "s1").unwrap();
+        move_to_trash(&store, "-home-g-project", "s1").unwrap();
         let trash = store.load_trash().unwrap();
-        store.restore_session_file(&trash[0]).unwrap();
+        store.restore_session_file(&trash[0], ConflictPolicy::Abort).unwrap();
 
         assert!(project_dir.join("s1.jsonl").exists());
         assert!(!tmp.path().join("trash/-home-g-project/s1.jsonl").exists());
+        assert!(!tmp.path().join("trash/-home-g-project/s1.trash.json").exists());
+    }
+
+    #[test]
+    fn test_move_to_trash_records_sidecar() {
+        let (tmp, store) = create_test_store();
+        let project_dir = tmp.path().join("projects/-home-g-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        let line = r#"{"type":"user","message":{"role":"user","content":"hi"},"uuid":"x"}"#;
+        fs::write(project_dir.join("s1.jsonl"), line).unwrap();
+
+        let mut session = Session::new("s1".to_string(), "-home-g-project".to_string());
+        session.slug = Some("fix login".to_string());
+        let trashed = store
+            .move_to_trash(&session, TrashReason::ZeroMessages, ConflictPolicy::Abort)
+            .unwrap();
+        let info = trashed.trashed.unwrap();
+        assert_eq!(info.original_path, project_dir.join("s1.jsonl"));
+        assert_eq!(info.title, "-home-g-project [fix login] (s1)");
+        assert_eq!(info.reason, TrashReason::ZeroMessages);
+        assert!(chrono::DateTime::parse_from_rfc3339(&info.deleted_at).is_ok());
+
+        let trashed = tmp.path().join("trash/-home-g-project/s1.jsonl");
+        let trash = store.load_trash().unwrap();
+        assert_eq!(trash[0].trashed.as_ref(), Some(&info));
+        assert_eq!(trash[0].list_date(), info.deleted_at);
+        match store.reload_file(&trashed) {
+            Some(SessionChange::Upserted { session, .. }) => {
+                assert_eq!(session.trashed, Some(info))
+            }
+            other => panic!("expected upsert, got {:?}", other),
+        }
+        // Sidecars are not sessions
+        assert_eq!(store.load_trash().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_restore_uses_recorded_original_path() {
+        let (tmp, store) = create_test_store();
+        let trash_dir = tmp.path().join("trash/-home-g-project");
+        fs::create_dir_all(&trash_dir).unwrap();
+        let line = r#"{"type":"user","message":{"role":"user","content":"hi"},"uuid":"x"}"#;
+        fs::write(trash_dir.join("s1.jsonl"), line).unwrap();
+        let original = tmp.path().join("projects/-home-g-old-name/s1.jsonl");
+        let info = TrashInfo {
+            deleted_at: "2026-01-01T10:00:00+00:00".to_string(),
+            original_path: original.clone(),
+            title: "old".to_string(),
+            reason: TrashReason::Manual,
+        };
+        fs::write(trash_dir.join("s1.trash.json"), serde_json::to_string(&info).unwrap()).unwrap();
+
+        let trash = store.load_trash().unwrap();
+        store.restore_session_file(&trash[0], ConflictPolicy::Abort).unwrap();
+        assert!(original.exists());
+        assert!(!tmp.path().join("projects/-home-g-project/s1.jsonl").exists());
+    }
+
+    #[test]
+    fn test_restore_ignores_recorded_path_outside_projects() {
+        let (tmp, store) = create_test_store();
+        let trash_dir = tmp.path().join("trash/-home-g-project");
+        fs::create_dir_all(&trash_dir).unwrap();
+        let line = r#"{"type":"user","message":{"role":"user","content":"hi"},"uuid":"x"}"#;
+        let outside = [
+            tmp.path().join("elsewhere/-home-g-project/s1.jsonl"),
+            tmp.path().join("projects/s1.jsonl"),
+            tmp.path().join("projects/../elsewhere/s1.jsonl"),
+        ];
+        for original in outside {
+            fs::write(trash_dir.join("s1.jsonl"), line).unwrap();
+            let info = TrashInfo {
+                deleted_at: "2026-01-01T10:00:00+00:00".to_string(),
+                original_path: original.clone(),
+                title: "old".to_string(),
+                reason: TrashReason::Manual,
+            };
+            let sidecar = serde_json::to_string(&info).unwrap();
+            fs::write(trash_dir.join("s1.trash.json"), sidecar).unwrap();
+
+            let trash = store.load_trash().unwrap();
+            let restored =
+                store.restore_session_file(&trash[0], ConflictPolicy::Abort).unwrap();
+            let expected = tmp.path().join("projects/-home-g-project/s1.jsonl");
+            assert_eq!(restored.jsonl_path, expected, "{}", original.display());
+            fs::remove_file(expected).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_move_to_trash_survives_failed_sidecar_write() {
+        let (tmp, store) = create_test_store();
+        let project_dir = tmp.path().join("projects/-home-g-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(project_dir.join("s1.jsonl"), "{}\n").unwrap();
+        // A directory in the sidecar's place makes the write fail
+        fs::create_dir_all(tmp.path().join("trash/-home-g-project/s1.trash.json")).unwrap();
+
+        let session = Session::new("s1".to_string(), "-home-g-project".to_string());
+        let trashed = store
+            .move_to_trash(&session, TrashReason::Manual, ConflictPolicy::Abort)
+            .unwrap();
+        assert!(trashed.trashed.is_none());
+        assert!(tmp.path().join("trash/-home-g-project/s1.jsonl").exists());
+        assert!(!project_dir.join("s1.jsonl").exists());
+    }
+
+    #[test]
+    fn test_restore_conflict_is_refused_without_resolution() {
+        let (tmp, store) = create_test_store();
+        let project_dir = tmp.path().join("projects/-home-g-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(project_dir.join("s1.jsonl"), "old\n").unwrap();
+        move_to_trash(&store, "-home-g-project", "s1").unwrap();
+        fs::write(project_dir.join("s1.jsonl"), "new\n").unwrap();
+
+        let trash = store.load_trash().unwrap();
+        assert_eq!(store.restore_conflict(&trash[0]), Some(project_dir.join("s1.jsonl")));
+        assert!(store.restore_session_file(&trash[0], ConflictPolicy::Abort).is_err());
+        assert!(store.restore_session_file(&trash[0], ConflictPolicy::Skip).is_err());
+        assert_eq!(fs::read_to_string(project_dir.join("s1.jsonl")).unwrap(), "new\n");
+        assert!(tmp.path().join("trash/-home-g-project/s1.jsonl").exists());
+
+        store.restore_session_file(&trash[0], ConflictPolicy::Overwrite).unwrap();
+        assert_eq!(fs::read_to_string(project_dir.join("s1.jsonl")).unwrap(), "old\n");
+        assert!(store.load_trash().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_restore_keep_both_assigns_new_id() {
+        let (tmp, store) = create_test_store();
+        let project_dir = tmp.path().join("projects/-home-g-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        let line = r#"{"type":"user","message":{"role":"user","content":"hi"},"uuid":"x","sessionId":"s1"}"#;
+        fs::write(project_dir.join("s1.jsonl"), format!("{}\n", line)).unwrap();
+        move_to_trash(&store, "-home-g-project", "s1").unwrap();
+        fs::write(project_dir.join("s1.jsonl"), "recreated\n").unwrap();
+
+        let trash = store.load_trash().unwrap();
+        let restored = store
+            .restore_session_file(&trash[0], ConflictPolicy::KeepBoth)
+            .unwrap();
+        assert_ne!(restored.id, "s1");
+        assert_eq!(restored.id.len(), 36, "UUID-shaped: {}", restored.id);
+        assert_eq!(restored.jsonl_path, project_dir.join(format!("{}.jsonl", restored.id)));
+        assert_eq!(restored.message_count, 1);
+        let copy = fs::read_to_string(&restored.jsonl_path).unwrap();
+        assert!(copy.contains(&format!(r#""sessionId":"{}""#, restored.id)), "{copy}");
+        assert_eq!(fs::read_to_string(project_dir.join("s1.jsonl")).unwrap(), "recreated\n");
+    }
+
+    #[test]
+    fn test_move_to_trash_merges_with_trashed_copy() {
+        let (tmp, store) = create_test_store();
+        let project_dir = tmp.path().join("projects/-home-g-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        let entry = |uuid: &str, ts: &str| {
+            format!(
+                r#"{{"type":"user","message":{{"role":"user","content":"{}"}},"uuid":"{}","timestamp":"{}"}}"#,
+                uuid, uuid, ts
+            )
+        };
+        let (u1, u2, u3) = (
+            entry("u1", "2026-01-01T10:00:00Z"),
+            entry("u2", "2026-01-01T10:02:00Z"),
+            entry("u3", "2026-01-01T10:03:00Z"),
+        );
+        fs::write(project_dir.join("s1.jsonl"), format!("{}\n{}\n", u1, u3)).unwrap();
+        move_to_trash(&store, "-home-g-project", "s1").unwrap();
+        fs::write(project_dir.join("s1.jsonl"), format!("{}\n{}\n", u1, u2)).unwrap();
+
+        let session = Session::new("s1".to_string(), "-home-g-project".to_string());
+        assert!(store.trash_conflict(&session).is_some());
+        assert!(store
+            .move_to_trash(&session, TrashReason::Manual, ConflictPolicy::Abort)
+            .is_err());
+        assert!(project_dir.join("s1.jsonl").exists(), "refused move leaves the file");
+
+        let merged = store
+            .move_to_trash(&session, TrashReason::Manual, ConflictPolicy::Merge)
+            .unwrap();
+        assert_eq!(merged.message_count, 3);
+        assert!(merged.trashed.is_some());
+        let content = fs::read_to_string(tmp.path().join("trash/-home-g-project/s1.jsonl"));
+        assert_eq!(content.unwrap(), format!("{}\n{}\n{}\n", u1, u2, u3));
+        assert!(!project_dir.join("s1.jsonl").exists());
+    }
+
+    #[test]
+    fn test_kept_copy_restores_under_its_own_id_and_deletes() {
+        let (tmp, store) = create_test_store();
+        let project_dir = tmp.path().join("projects/-home-g-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(project_dir.join("s1.jsonl"), "{}\n").unwrap();
+        move_to_trash(&store, "-home-g-project", "s1").unwrap();
+        fs::write(project_dir.join("s1.jsonl"), "{}\n").unwrap();
+        let session = Session::new("s1".to_string(), "-home-g-project".to_string());
+        let kept = store
+            .move_to_trash(&session, TrashReason::Manual, ConflictPolicy::KeepBoth)
+            .unwrap();
+
+        // The sidecar records s1.jsonl, but the copy goes back under its new ID
+        assert_eq!(store.restore_conflict(&kept), None);
+        let restored = store.restore_session_file(&kept, ConflictPolicy::Abort).unwrap();
+        assert_eq!(restored.jsonl_path, project_dir.join(format!("{}.jsonl", kept.id)));
+
+        let trash = store.load_trash().unwrap();
+        store.delete_from_trash(&trash[0]).unwrap();
+        assert!(!tmp.path().join("trash/-home-g-project").exists());
+        assert!(store.load_trash().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_merge_jsonl_keeps_untimed_lines_in_place() {
+        let existing = concat!(
+            r#"{"uuid":"a","timestamp":"2026-01-01T10:00:00Z"}"#, "\n",
+            r#"{"uuid":"c","timestamp":"2026-01-01T10:05:00Z"}"#, "\n",
+        );
+        let incoming = concat!(
+            r#"{"uuid":"a","timestamp":"2026-01-01T10:00:00Z"}"#, "\n",
+            r#"{"uuid":"b","timestamp":"2026-01-01T10:01:00Z"}"#, "\n",
+            r#"{"type":"custom-title","customTitle":"x"}"#, "\n",
+        );
+        let merged = merge_jsonl(existing, incoming);
+        let uuids: Vec<&str> = merged
+            .lines()
+            .map(|l| if l.contains("custom-title") { "title" } else { &l[9..10] })
+            .collect();
+        assert_eq!(uuids, ["a", "b", "title", "c"]);
+        // Merging the same content again changes nothing
+        assert_eq!(merge_jsonl(&merged, incoming), merged);
+    }
+
+    /// Trashed session `id` whose sidecar says it was deleted `days_ago` days before `now`.
+    fn trashed_days_ago(
+        tmp: &TempDir,
+        id: &str,
+        days_ago: i64,
+        bytes: usize,
+        now: DateTime<Local>,
+    ) {
+        let trash_dir = tmp.path().join("trash/-home-g-project");
+        fs::create_dir_all(&trash_dir).unwrap();
+        fs::write(trash_dir.join(format!("{}.jsonl", id)), "x".repeat(bytes)).unwrap();
+        let info = TrashInfo {
+            deleted_at: (now - chrono::Duration::days(days_ago)).to_rfc3339(),
+            original_path: PathBuf::from(format!("/p/{}.jsonl", id)),
+            title: id.to_string(),
+            reason: TrashReason::Manual,
+        };
+        let sidecar = serde_json::to_string(&info).unwrap();
+        fs::write(trash_dir.join(format!("{}.trash.json", id)), sidecar).unwrap();
+    }
+
+    #[test]
+    fn test_purge_trash_by_age() {
+        let (tmp, store) = create_test_store();
+        let now = Local::now();
+        trashed_days_ago(&tmp, "old", 40, 10, now);
+        trashed_days_ago(&tmp, "new", 2, 10, now);
+        let retention = TrashRetention {
+            max_age_days: Some(30),
+            max_size_mb: None,
+        };
+
+        let purge = store.purge_trash(&retention, now).unwrap();
+        assert_eq!(purge.purged, ["old"]);
+        assert_eq!(purge.expired, 1);
+        assert!(purge.freed_bytes > 10, "sidecar counts too");
+        let trash_dir = tmp.path().join("trash/-home-g-project");
+        assert!(!trash_dir.join("old.jsonl").exists());
+        assert!(!trash_dir.join("old.trash.json").exists());
+        assert!(trash_dir.join("new.jsonl").exists());
+
+        // Nothing left to do on the second run
+        assert_eq!(store.purge_trash(&retention, now).unwrap(), TrashPurge::default());
+    }
+
+    #[test]
+    fn test_purge_trash_by_size_removes_oldest_first() {
+        let (tmp, store) = create_test_store();
+        let now = Local::now();
+        let mb = 1024 * 1024;
+        trashed_days_ago(&tmp, "a", 3, mb, now);
+        trashed_days_ago(&tmp, "b", 2, mb, now);
+        trashed_days_ago(&tmp, "c", 1, mb / 2, now);
+        // Without a sidecar the file's mtime (now) counts as deletion time
+        let trash_dir = tmp.path().join("trash/-home-g-project");
+        fs::write(trash_dir.join("d.jsonl"), "x".repeat(mb / 4)).unwrap();
+
+        let retention = TrashRetention {
+            max_age_days: Some(30),
+            max_size_mb: Some(1),
+        };
+        let purge = store.purge_trash(&retention, now).unwrap();
+        assert_eq!(purge.purged, ["a", "b"]);
+        assert_eq!(purge.expired, 0);
+        assert!(store.disk_usage().trash <= mb as u64);
+        assert_eq!(
+            purge.describe(),
+            "Purged 2 session(s) from the trash (2 over the size limit), 2.0 MB freed"
+        );
+    }
+
+    #[test]
+    fn test_purge_trash_removes_empty_project_dirs_and_ignores_disabled_policy() {
+        let (tmp, store) = create_test_store();
+        let now = Local::now();
+        trashed_days_ago(&tmp, "old", 10, 5, now);
+
+        let disabled = TrashRetention::default();
+        assert!(store.purge_trash(&disabled, now).unwrap().purged.is_empty());
+
+        let retention = TrashRetention {
+            max_age_days: Some(7),
+            max_size_mb: None,
+        };
+        store.purge_trash(&retention, now).unwrap();
+        assert!(!tmp.path().join("trash/-home-g-project").exists());
+        assert!(store.load_trash().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_purge_trash_continues_after_failed_delete() {
+        let (tmp, store) = create_test_store();
+        let now = Local::now();
+        trashed_days_ago(&tmp, "stuck", 50, 5, now);
+        trashed_days_ago(&tmp, "old", 40, 5, now);
+        // A directory in place of the transcript cannot be removed with remove_file
+        let stuck = tmp.path().join("trash/-home-g-project/stuck.jsonl");
+        fs::remove_file(&stuck).unwrap();
+        fs::create_dir(&stuck).unwrap();
+
+        let retention = TrashRetention {
+            max_age_days: Some(30),
+            max_size_mb: None,
+        };
+        let purge = store.purge_trash(&retention, now).unwrap();
+        assert_eq!(purge.purged, ["old"]);
+        assert_eq!(purge.failed.len(), 1);
+        assert_eq!(purge.failed[0].id, "stuck");
+        assert!(purge.describe().ends_with("; 1 session(s) could not be purged"));
+        assert!(!tmp.path().join("trash/-home-g-project/old.jsonl").exists());
+    }
+
+    #[test]
+    fn test_purge_trash_with_huge_limits_purges_nothing() {
+        let (tmp, store) = create_test_store();
+        let now = Local::now();
+        trashed_days_ago(&tmp, "old", 400, 5, now);
+        let retention = TrashRetention {
+            max_age_days: Some(u32::MAX),
+            max_size_mb: Some(u64::MAX),
+        };
+        assert_eq!(store.purge_trash(&retention, now).unwrap(), TrashPurge::default());
     }
 
     #[test]
@@ -670,7 +1834,7 @@ mod tests {
         let line = r#"{"type":"user","message":{"role":"user","content":"hi"},"uuid":"x"}"#;
         fs::write(project_dir.join("s1.jsonl"), line).unwrap();
 
-        store.move_to_trash("-home-g-project", "s1").unwrap();
+        move_to_trash(&store, "-home-g-project", "s1").unwrap();
         assert!(tmp.path().join("trash").exists());
 
         store.empty_trash().unwrap();
@@ -695,6 +1859,25 @@ mod tests {
         assert_eq!(sessions[0].id, "s1");
     }
 
+    #[test]
+    fn test_message_count_follows_active_branch() {
+        let (tmp, store) = create_test_store();
+        let project_dir = tmp.path().join("projects/-home-g-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        // b wurde verworfen: der aktive Zweig ist a → c → d
+        let content = [
+            r#"{"type":"user","message":{"role":"user","content":"a"},"uuid":"a"}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":"b"},"uuid":"b","parentUuid":"a"}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":"c"},"uuid":"c","parentUuid":"a"}"#,
+            r#"{"type":"user","message":{"role":"user","content":"d"},"uuid":"d","parentUuid":"c"}"#,
+        ]
+        .join("\n");
+        fs::write(project_dir.join("s1.jsonl"), content).unwrap();
+
+        let sessions = store.load_sessions().unwrap();
+        assert_eq!(sessions[0].message_count, 3);
+    }
+
     #[test]
     fn test_new_uses_env_var_claude_data_dir() {
         let tmp = tempfile::TempDir::new().unwrap();
@@ -725,11 +1908,54 @@ mod tests {
         assert_eq!(sessions.len(), 1);
         assert_eq!(sessions[0].id, "test-session");
 
-        store
-            .move_to_trash("-home-g-myproject", "test-session")
-            .unwrap();
+        move_to_trash(&store, "-home-g-myproject", "test-session").unwrap();
 
         let sessions_after_delete = store.load_sessions().unwrap();
         assert_eq!(sessions_after_delete.len(), 0);
     }
+
+    #[test]
+    fn test_reload_file_upserts_existing_and_reports_removed() {
+        let (tmp, store) = create_test_store();
+        let project_dir = tmp.path().join("projects/-home-g-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        let path = project_dir.join("s1.jsonl");
+        fs::write(
+            &path,
+            r#"{"type":"user","message":{"role":"user","content":"hello"},"uuid":"x"}"#,
+        )
+        .unwrap();
+
+        match store.reload_file(&path) {
+            Some(SessionChange::Upserted { in_trash, session }) => {
+                assert!(!in_trash);
+                assert_eq!(session.id, "s1");
+                assert_eq!(session.project_name, "-home-g-project");
+                assert_eq!(session.message_count, 1);
+            }
+            other => panic!("expected upsert, got {:?}", other),
+        }
+
+        fs::remove_file(&path).unwrap();
+        match store.reload_file(&path) {
+            Some(SessionChange::Removed { in_trash, id }) => {
+                assert!(!in_trash);
+                assert_eq!(id, "s1");
+            }
+            other => panic!("expected removal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_reload_file_detects_trash_and_ignores_foreign_paths() {
+        let (tmp, store) = create_test_store();
+        let trash_file = tmp.path().join("trash/-p/t1.jsonl");
+        assert!(matches!(
+            store.reload_file(&trash_file),
+            Some(SessionChange::Removed { in_trash: true, .. })
+        ));
+        assert!(store.reload_file(&tmp.path().join("projects/-p/notes.txt")).is_none());
+        assert!(store.reload_file(&tmp.path().join("projects/-p/sub/x.jsonl")).is_none());
+        assert!(store.reload_file(&tmp.path().join("other/-p/x.jsonl")).is_none());
+    }
 }
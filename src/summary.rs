@@ -0,0 +1,331 @@
+use crate::models::{Message, MessagePart, Session, TokenUsage, TrashInfo, TrashReason};
+use crate::pricing::Pricing;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Version of the `SessionSummary` JSON schema. Bump it when fields are renamed,
+/// removed or change their meaning; adding optional fields keeps the version.
+pub const SUMMARY_SCHEMA_VERSION: u32 = 1;
+
+/// Stable, machine-readable description of a session for `--json`/`--ndjson`
+/// output. Built from its own types rather than `Session` and the models, so
+/// internal changes do not break consumers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub schema_version: u32,
+    pub id: String,
+    /// Project directory name as written by Claude Code (e.g. `-home-g-myproject`).
+    pub project_path: String,
+    /// The project's real directory, if it could be resolved on this machine.
+    pub resolved_path: Option<String>,
+    /// Location of the session's JSONL file.
+    pub file: String,
+    pub title: Option<String>,
+    pub in_trash: bool,
+    /// When, from where and why the session was trashed, if that was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trashed: Option<TrashSummary>,
+    pub created_at: String,
+    pub updated_at: String,
+    /// First and last message timestamps (RFC 3339).
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub size_bytes: u64,
+    pub entry_count: usize,
+    pub message_count: usize,
+    pub first_prompt: Option<String>,
+    pub tokens: TokenSummary,
+    /// Estimated from the configured pricing table; models without a price count as 0.
+    pub estimated_cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenSummary {
+    pub total: u64,
+    pub input: u64,
+    pub output: u64,
+    pub cache_creation: u64,
+    pub cache_read: u64,
+    pub by_model: BTreeMap<String, ModelTokens>,
+}
+
+/// Token counts of one model, mapped from `TokenUsage`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelTokens {
+    pub input: u64,
+    pub output: u64,
+    pub cache_creation: u64,
+    pub cache_read: u64,
+}
+
+/// Trash record of a session; kept apart from the `TrashInfo` sidecar format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrashSummary {
+    /// RFC 3339 timestamp of the move.
+    pub deleted_at: String,
+    pub original_path: String,
+    pub title: String,
+    /// `manual`, `zero-messages`, `bulk` or `secret-scan`.
+    pub reason: String,
+}
+
+/// `show --json` and JSON export: the summary plus the messages of one branch.
+#[derive(Debug, Serialize)]
+pub struct SessionDetail {
+    #[serde(flatten)]
+    pub summary: SessionSummary,
+    pub messages: Vec<MessageSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MessageSummary {
+    pub role: String,
+    /// Cleaned text of all text parts.
+    pub content: String,
+    pub parts: Vec<PartSummary>,
+    pub timestamp: Option<String>,
+    pub uuid: Option<String>,
+    pub parent_uuid: Option<String>,
+}
+
+/// One content block of a message, tagged by `type`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PartSummary {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        is_error: bool,
+        output: String,
+    },
+    Thinking {
+        text: String,
+    },
+}
+
+impl SessionDetail {
+    pub fn new(summary: SessionSummary, messages: &[&Message]) -> Self {
+        Self {
+            summary,
+            messages: messages.iter().map(|m| MessageSummary::from(*m)).collect(),
+        }
+    }
+}
+
+impl From<&Message> for MessageSummary {
+    fn from(message: &Message) -> Self {
+        Self {
+            role: message.role.clone(),
+            content: message.content.clone(),
+            parts: message.parts.iter().map(PartSummary::from).collect(),
+            timestamp: message.timestamp.clone(),
+            uuid: message.uuid.clone(),
+            parent_uuid: message.parent_uuid.clone(),
+        }
+    }
+}
+
+impl From<&MessagePart> for PartSummary {
+    fn from(part: &MessagePart) -> Self {
+        match part {
+            MessagePart::Text { text } => PartSummary::Text { text: text.clone() },
+            MessagePart::ToolUse { id, name, input } => PartSummary::ToolUse {
+                id: id.clone(),
+                name: name.clone(),
+                input: input.clone(),
+            },
+            MessagePart::ToolResult {
+                tool_use_id,
+                is_error,
+                output,
+            } => PartSummary::ToolResult {
+                tool_use_id: tool_use_id.clone(),
+                is_error: *is_error,
+                output: output.clone(),
+            },
+            MessagePart::Thinking { text } => PartSummary::Thinking { text: text.clone() },
+        }
+    }
+}
+
+impl From<&TokenUsage> for ModelTokens {
+    fn from(usage: &TokenUsage) -> Self {
+        Self {
+            input: usage.input,
+            output: usage.output,
+            cache_creation: usage.cache_creation,
+            cache_read: usage.cache_read,
+        }
+    }
+}
+
+impl From<&TrashInfo> for TrashSummary {
+    fn from(info: &TrashInfo) -> Self {
+        Self {
+            deleted_at: info.deleted_at.clone(),
+            original_path: info.original_path.to_string_lossy().to_string(),
+            title: info.title.clone(),
+            reason: match info.reason {
+                TrashReason::Manual => "manual",
+                TrashReason::ZeroMessages => "zero-messages",
+                TrashReason::Bulk => "bulk",
+                TrashReason::SecretScan => "secret-scan",
+            }
+            .to_string(),
+        }
+    }
+}
+
+impl SessionSummary {
+    pub fn new(session: &Session, pricing: &Pricing, in_trash: bool) -> Self {
+        let mut totals = TokenUsage::default();
+        for usage in session.token_usage.values() {
+            totals.add(usage);
+        }
+        Self {
+            schema_version: SUMMARY_SCHEMA_VERSION,
+            id: session.id.clone(),
+            project_path: session.project_name.clone(),
+            // Unresolvable projects fall back to the directory name in `Session`
+            resolved_path: (session.project_path != session.project_name)
+                .then(|| session.project_path.clone()),
+            file: session.jsonl_path.to_string_lossy().to_string(),
+            title: session.slug.clone(),
+            in_trash,
+            trashed: session.trashed.as_ref().map(TrashSummary::from),
+            created_at: session.created_at.clone(),
+            updated_at: session.updated_at.clone(),
+            started_at: session.started_at.clone(),
+            ended_at: session.ended_at.clone(),
+            size_bytes: session.size,
+            entry_count: session.total_entries,
+            message_count: session.message_count,
+            first_prompt: session.first_prompt.clone(),
+            tokens: TokenSummary {
+                total: totals.total(),
+                input: totals.input,
+                output: totals.output,
+                cache_creation: totals.cache_creation,
+                cache_read: totals.cache_read,
+                by_model: session
+                    .token_usage
+                    .iter()
+                    .map(|(model, usage)| (model.clone(), ModelTokens::from(usage)))
+                    .collect(),
+            },
+            estimated_cost_usd: pricing.cost(&session.token_usage),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn session() -> Session {
+        let mut s = Session::new("abc-123".to_string(), "/home/g/proj".to_string());
+        s.project_name = "-home-g-proj".to_string();
+        s.jsonl_path = PathBuf::from("/data/projects/-home-g-proj/abc-123.jsonl");
+        s.slug = Some("my-title".to_string());
+        s.message_count = 4;
+        s.token_usage.insert(
+            "claude-sonnet-4".to_string(),
+            TokenUsage {
+                input: 1_000_000,
+                output: 0,
+                cache_creation: 0,
+                cache_read: 10,
+            },
+        );
+        s
+    }
+
+    #[test]
+    fn test_summary_fields() {
+        let summary = SessionSummary::new(&session(), &Pricing::default(), false);
+        assert_eq!(summary.schema_version, SUMMARY_SCHEMA_VERSION);
+        assert_eq!(summary.project_path, "-home-g-proj");
+        assert_eq!(summary.resolved_path.as_deref(), Some("/home/g/proj"));
+        assert_eq!(summary.file, "/data/projects/-home-g-proj/abc-123.jsonl");
+        assert_eq!(summary.title.as_deref(), Some("my-title"));
+        assert_eq!(summary.tokens.total, 1_000_010);
+        assert_eq!(summary.tokens.cache_read, 10);
+        assert!(summary.estimated_cost_usd > 3.0);
+    }
+
+    #[test]
+    fn test_unresolved_project_has_no_resolved_path() {
+        let mut s = session();
+        s.project_path = s.project_name.clone();
+        assert_eq!(SessionSummary::new(&s, &Pricing::default(), true).resolved_path, None);
+    }
+
+    #[test]
+    fn test_summary_json_field_names_are_stable() {
+        let summary = SessionSummary::new(&session(), &Pricing::default(), false);
+        let value = serde_json::to_value(summary).unwrap();
+        let keys: Vec<&str> = value.as_object().unwrap().keys().map(String::as_str).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "created_at",
+                "ended_at",
+                "entry_count",
+                "estimated_cost_usd",
+                "file",
+                "first_prompt",
+                "id",
+                "in_trash",
+                "message_count",
+                "project_path",
+                "resolved_path",
+                "schema_version",
+                "size_bytes",
+                "started_at",
+                "title",
+                "tokens",
+                "updated_at",
+            ]
+        );
+        assert_eq!(value["tokens"]["by_model"]["claude-sonnet-4"]["input"], 1_000_000);
+    }
+
+    #[test]
+    fn test_detail_messages_and_trash_shape() {
+        let mut s = session();
+        s.trashed = Some(TrashInfo {
+            deleted_at: "2026-01-15T10:00:00Z".to_string(),
+            original_path: PathBuf::from("/data/projects/-home-g-proj/abc-123.jsonl"),
+            title: "my-title".to_string(),
+            reason: TrashReason::ZeroMessages,
+        });
+        let message = Message {
+            role: "assistant".to_string(),
+            content: "hi".to_string(),
+            parts: vec![MessagePart::ToolResult {
+                tool_use_id: "t1".to_string(),
+                is_error: true,
+                output: "boom".to_string(),
+            }],
+            timestamp: None,
+            uuid: None,
+            parent_uuid: None,
+        };
+        let summary = SessionSummary::new(&s, &Pricing::default(), true);
+        let value = serde_json::to_value(SessionDetail::new(summary, &[&message])).unwrap();
+        assert_eq!(value["trashed"]["reason"], "zero-messages");
+        let original = "/data/projects/-home-g-proj/abc-123.jsonl";
+        assert_eq!(value["trashed"]["original_path"], original);
+        assert_eq!(value["messages"][0]["content"], "hi");
+        assert_eq!(value["messages"][0]["parts"][0]["type"], "tool_result");
+        assert_eq!(value["messages"][0]["parts"][0]["is_error"], true);
+    }
+}
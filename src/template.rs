@@ -0,0 +1,388 @@
+use crate::export::{self, Exporter};
+use crate::models::{format_timestamp, Message, MessagePart, Session};
+use crate::pricing::Pricing;
+use crate::summary::SessionSummary;
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// A user-defined export layout in a small Mustache dialect:
+///
+/// - `{{name}}` inserts a value (dotted paths like `session.id` work, `{{.}}` is
+///   the current item). Values are inserted as-is, without escaping.
+/// - `{{#name}}…{{/name}}` repeats for every element of a list, renders once for
+///   any other non-empty value and is skipped for `false`, `null`, `""` and `[]`.
+/// - `{{^name}}…{{/name}}` renders only if the value is empty.
+/// - `{{! comment }}` is dropped.
+///
+/// Section and comment tags alone on a line remove that line entirely, so
+/// templates can be laid out readably.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Variable(String),
+    Section {
+        name: String,
+        inverted: bool,
+        children: Vec<Node>,
+    },
+}
+
+/// Tag kinds after tokenizing, before sections are nested.
+enum Token {
+    Text(String),
+    Variable(String),
+    Open { name: String, inverted: bool },
+    Close(String),
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut stack: Vec<(String, bool, Vec<Node>)> = Vec::new();
+        let mut nodes = Vec::new();
+        for token in tokens {
+            match token {
+                Token::Text(text) => nodes.push(Node::Text(text)),
+                Token::Variable(name) => nodes.push(Node::Variable(name)),
+                Token::Open { name, inverted } => {
+                    stack.push((name, inverted, std::mem::take(&mut nodes)));
+                }
+                Token::Close(name) => {
+                    let Some((open, inverted, parent)) = stack.pop() else {
+                        bail!("unexpected {{{{/{}}}}} without an opening tag", name);
+                    };
+                    if open != name {
+                        bail!("{{{{/{}}}}} closes {{{{#{}}}}}", name, open);
+                    }
+                    let children = std::mem::replace(&mut nodes, parent);
+                    nodes.push(Node::Section {
+                        name,
+                        inverted,
+                        children,
+                    });
+                }
+            }
+        }
+        if let Some((open, _, _)) = stack.pop() {
+            bail!("section {{{{#{}}}}} is never closed", open);
+        }
+        Ok(Self { nodes })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read template {}", path.display()))?;
+        Self::parse(&source).with_context(|| format!("invalid template {}", path.display()))
+    }
+
+    pub fn render(&self, data: &Value) -> String {
+        let mut out = String::new();
+        render_nodes(&self.nodes, &mut vec![data], &mut out);
+        out
+    }
+}
+
+/// Templates replace the built-in Markdown layout.
+impl Exporter for Template {
+    fn extension(&self) -> &'static str {
+        "md"
+    }
+
+    fn render(
+        &self,
+        session: &Session,
+        messages: &[&Message],
+        pricing: &Pricing,
+    ) -> Result<String> {
+        Ok(Template::render(self, &context(session, messages, pricing)))
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    let mut text = String::new();
+    // Whether the current output line so far holds only whitespace
+    let mut line_blank = true;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            bail!("unclosed tag: {}", rest[start..].lines().next().unwrap_or(""));
+        };
+        let tag = rest[start + 2..start + 2 + len].trim();
+        let mut before = &rest[..start];
+        let mut after = &rest[start + len + 4..];
+
+        let (sigil, name) = match tag.chars().next() {
+            Some(c @ ('#' | '^' | '/' | '!')) => (Some(c), tag[1..].trim()),
+            _ => (None, tag),
+        };
+        if sigil != Some('!') && name.is_empty() {
+            bail!("empty tag {{{{{}}}}}", tag);
+        }
+        // A block tag alone on its line takes the whole line with it
+        if sigil.is_some() {
+            let prefix_blank = match before.rfind('\n') {
+                Some(i) => before[i + 1..].trim().is_empty(),
+                None => line_blank && before.trim().is_empty(),
+            };
+            let line_end = after.find('\n');
+            let suffix_blank = after[..line_end.unwrap_or(after.len())].trim().is_empty();
+            if prefix_blank && suffix_blank {
+                before = before.trim_end_matches([' ', '\t']);
+                if !before.contains('\n') {
+                    text.truncate(text.trim_end_matches([' ', '\t']).len());
+                }
+                after = line_end.map_or("", |i| &after[i + 1..]);
+                line_blank = true;
+            }
+        }
+        line_blank = match before.rfind('\n') {
+            Some(i) => before[i + 1..].trim().is_empty(),
+            None => line_blank && before.trim().is_empty(),
+        };
+        text.push_str(before);
+
+        let token = match sigil {
+            Some('!') => None,
+            Some('#') => Some(Token::Open {
+                name: name.to_string(),
+                inverted: false,
+            }),
+            Some('^') => Some(Token::Open {
+                name: name.to_string(),
+                inverted: true,
+            }),
+            Some('/') => Some(Token::Close(name.to_string())),
+            _ => Some(Token::Variable(name.to_string())),
+        };
+        if matches!(token, Some(Token::Variable(_))) {
+            line_blank = false;
+        }
+        if let Some(token) = token {
+            if !text.is_empty() {
+                tokens.push(Token::Text(std::mem::take(&mut text)));
+            }
+            tokens.push(token);
+        }
+        rest = after;
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    Ok(tokens)
+}
+
+fn render_nodes<'a>(nodes: &'a [Node], stack: &mut Vec<&'a Value>, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Variable(name) => match lookup(stack, name) {
+                Some(Value::String(s)) => out.push_str(s),
+                Some(Value::Null) | None => {}
+                Some(value) => out.push_str(&value.to_string()),
+            },
+            Node::Section {
+                name,
+                inverted,
+                children,
+            } => {
+                let value = lookup(stack, name);
+                let truthy = value.is_some_and(is_truthy);
+                if *inverted {
+                    if !truthy {
+                        render_nodes(children, stack, out);
+                    }
+                    continue;
+                }
+                match value {
+                    Some(Value::Array(items)) => {
+                        for item in items {
+                            stack.push(item);
+                            render_nodes(children, stack, out);
+                            stack.pop();
+                        }
+                    }
+                    Some(value) if truthy => {
+                        stack.push(value);
+                        render_nodes(children, stack, out);
+                        stack.pop();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Resolves `name` against the innermost context that has its first segment.
+fn lookup<'a>(stack: &[&'a Value], name: &str) -> Option<&'a Value> {
+    if name == "." {
+        return stack.last().copied();
+    }
+    let mut segments = name.split('.');
+    let first = segments.next()?;
+    let mut value = stack.iter().rev().find_map(|frame| frame.get(first))?;
+    for segment in segments {
+        value = value.get(segment)?;
+    }
+    Some(value)
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null | Value::Bool(false) => false,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => true,
+    }
+}
+
+/// Data available to templates: `session` (the JSON summary plus formatted
+/// fields), `metadata` (the header rows of the built-in formats), `messages`
+/// and `exported_at`.
+pub fn context(session: &Session, messages: &[&Message], pricing: &Pricing) -> Value {
+    let in_trash = session.trashed.is_some();
+    let mut summary = serde_json::to_value(SessionSummary::new(session, pricing, in_trash))
+        .unwrap_or_else(|_| json!({}));
+    let metadata = export::metadata(session, pricing);
+    if let Some(fields) = summary.as_object_mut() {
+        fields.insert("name".into(), session.display_name().into());
+        fields.insert("project".into(), session.display_project_name().into());
+        for (label, value) in &metadata {
+            match *label {
+                "Duration" => fields.insert("duration".into(), value.as_str().into()),
+                "Tokens" => fields.insert("tokens_formatted".into(), value.as_str().into()),
+                "Estimated cost" => fields.insert("cost".into(), value.as_str().into()),
+                _ => None,
+            };
+        }
+    }
+    json!({
+        "session": summary,
+        "metadata": metadata
+            .iter()
+            .map(|(label, value)| json!({ "label": label, "value": value }))
+            .collect::<Vec<_>>(),
+        "messages": messages.iter().map(|m| message_context(m)).collect::<Vec<_>>(),
+        "exported_at": chrono::Local::now().to_rfc3339(),
+    })
+}
+
+fn message_context(msg: &Message) -> Value {
+    let tool_output = msg.is_tool_output();
+    let text: Vec<&str> = msg
+        .parts
+        .iter()
+        .filter_map(|p| match p {
+            MessagePart::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    json!({
+        "role": msg.role,
+        "is_user": msg.role == "user" && !tool_output,
+        "is_assistant": msg.role == "assistant",
+        "is_tool_output": tool_output,
+        "speaker": if msg.role == "user" { "You" } else { "Assistant" },
+        "timestamp": msg.timestamp,
+        "time": msg.timestamp.as_deref().map(format_timestamp),
+        "text": text.join("\n\n"),
+        "parts": msg.parts.iter().map(part_context).collect::<Vec<_>>(),
+    })
+}
+
+fn part_context(part: &MessagePart) -> Value {
+    match part {
+        MessagePart::Text { text } => json!({ "is_text": true, "text": text }),
+        MessagePart::Thinking { text } => json!({ "is_thinking": true, "text": text }),
+        MessagePart::ToolUse { name, input, .. } => json!({
+            "is_tool_use": true,
+            "name": name,
+            "summary": MessagePart::tool_input_summary(input),
+            "input": serde_json::to_string_pretty(input).unwrap_or_default(),
+        }),
+        MessagePart::ToolResult {
+            is_error, output, ..
+        } => json!({
+            "is_tool_result": true,
+            "is_error": is_error,
+            "output": output,
+            "line_count": output.lines().count(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(source: &str, data: Value) -> String {
+        Template::parse(source).unwrap().render(&data)
+    }
+
+    #[test]
+    fn test_variables_and_dotted_paths() {
+        let data = json!({ "a": "x", "n": 3, "s": { "id": "abc" }, "none": null });
+        assert_eq!(render("{{a}}-{{ n }}-{{s.id}}-{{none}}{{missing}}", data), "x-3-abc-");
+    }
+
+    #[test]
+    fn test_sections_loop_and_conditionals() {
+        let data = json!({
+            "items": [{ "v": 1 }, { "v": 2 }],
+            "title": "",
+            "flag": true,
+            "outer": "o",
+        });
+        assert_eq!(render("{{#items}}[{{v}}{{outer}}]{{/items}}", data.clone()), "[1o][2o]");
+        assert_eq!(render("{{#title}}T{{/title}}{{^title}}none{{/title}}", data.clone()), "none");
+        assert_eq!(render("{{#flag}}yes{{/flag}}{{! ignored }}", data.clone()), "yes");
+        assert_eq!(render("{{#items}}{{.}}{{/items}}", json!({ "items": ["a", "b"] })), "ab");
+    }
+
+    #[test]
+    fn test_standalone_tags_remove_their_line() {
+        let source = "Head\n{{#items}}\n- {{.}}\n{{/items}}\n  {{! note }}\nEnd\n";
+        assert_eq!(render(source, json!({ "items": ["a", "b"] })), "Head\n- a\n- b\nEnd\n");
+        // Inline tags keep the surrounding text
+        assert_eq!(render("a {{#t}}b{{/t}} c\n", json!({ "t": true })), "a b c\n");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Template::parse("{{#a}}x").unwrap_err().to_string().contains("never closed"));
+        assert!(Template::parse("{{#a}}{{/b}}").is_err());
+        assert!(Template::parse("{{/a}}").is_err());
+        assert!(Template::parse("{{a").unwrap_err().to_string().contains("unclosed"));
+    }
+
+    #[test]
+    fn test_context_for_session() {
+        let mut session = Session::new("abc-1".to_string(), "/home/g/proj".to_string());
+        session.slug = Some("Fix login".to_string());
+        let user = Message {
+            role: "user".to_string(),
+            content: "Hi".to_string(),
+            parts: vec![MessagePart::Text {
+                text: "Hi".to_string(),
+            }],
+            timestamp: None,
+            uuid: None,
+            parent_uuid: None,
+        };
+        let data = context(&session, &[&user], &Pricing::default());
+        let out = render(
+            "---\ntitle: {{session.title}}\nid: {{session.id}}\n---\n\
+             {{#messages}}{{#is_user}}> {{text}}\n{{/is_user}}{{/messages}}",
+            data,
+        );
+        assert_eq!(out, "---\ntitle: Fix login\nid: abc-1\n---\n> Hi\n");
+    }
+}
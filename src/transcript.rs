@@ -0,0 +1,158 @@
+use crate::models::Session;
+use crate::tree::ConversationTree;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Number of parsed transcripts kept in memory.
+const TRANSCRIPT_CACHE_SIZE: usize = 16;
+
+/// Cache key: the file plus its size, so an appended session is re-read.
+type TranscriptKey = (PathBuf, u64);
+
+/// LRU of recently viewed transcripts. Sessions only carry a summary, so the
+/// preview asks this cache for the message tree instead of re-reading the file every frame.
+pub struct TranscriptCache {
+    capacity: usize,
+    /// Most recently used entry at the front.
+    entries: VecDeque<(TranscriptKey, Arc<ConversationTree>)>,
+}
+
+impl TranscriptCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Returns the message tree of `session`, loading it from disk on a miss.
+    /// Unreadable files yield an empty transcript (cached too, to avoid re-reading each frame).
+    pub fn get(&mut self, session: &Session) -> Arc<ConversationTree> {
+        let key = (session.jsonl_path.clone(), session.size);
+        if let Some(pos) = self.entries.iter().position(|(k, _)| *k == key) {
+            let entry = self.entries.remove(pos).expect("position is valid");
+            let tree = Arc::clone(&entry.1);
+            self.entries.push_front(entry);
+            return tree;
+        }
+        let tree = Arc::new(ConversationTree::build(
+            session.load_messages().unwrap_or_default(),
+        ));
+        self.put(key, Arc::clone(&tree));
+        tree
+    }
+
+    /// Seeds the cache with already known messages (tests build sessions without files).
+    #[cfg(test)]
+    pub fn insert(&mut self, session: &Session, messages: Vec<crate::models::Message>) {
+        self.put(
+            (session.jsonl_path.clone(), session.size),
+            Arc::new(ConversationTree::build(messages)),
+        );
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn put(&mut self, key: TranscriptKey, tree: Arc<ConversationTree>) {
+        self.entries.retain(|(k, _)| *k != key);
+        self.entries.push_front((key, tree));
+        self.entries.truncate(self.capacity);
+    }
+}
+
+impl Default for TranscriptCache {
+    fn default() -> Self {
+        Self::new(TRANSCRIPT_CACHE_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn session_at(path: PathBuf) -> Session {
+        let mut s = Session::new("s".to_string(), "/p".to_string());
+        s.size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        s.jsonl_path = path;
+        s
+    }
+
+    fn write_msg(path: &std::path::Path, text: &str) {
+        fs::write(
+            path,
+            format!(
+                r#"{{"type":"user","message":{{"role":"user","content":"{}"}},"uuid":"u"}}"#,
+                text
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_loads_and_caches() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("a.jsonl");
+        write_msg(&path, "first");
+        let session = session_at(path.clone());
+        let mut cache = TranscriptCache::new(4);
+
+        assert_eq!(cache.get(&session).messages()[0].content, "first");
+        // Same size, different content: served from the cache
+        write_msg(&path, "FIRST");
+        assert_eq!(cache.get(&session).messages()[0].content, "first");
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_get_reloads_when_size_changes() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("a.jsonl");
+        write_msg(&path, "short");
+        let mut cache = TranscriptCache::new(4);
+        cache.get(&session_at(path.clone()));
+
+        write_msg(&path, "much longer text");
+        assert_eq!(
+            cache.get(&session_at(path)).messages()[0].content,
+            "much longer text"
+        );
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let tmp = TempDir::new().unwrap();
+        let sessions: Vec<Session> = ["a", "b", "c"]
+            .iter()
+            .map(|name| {
+                let path = tmp.path().join(format!("{name}.jsonl"));
+                write_msg(&path, name);
+                session_at(path)
+            })
+            .collect();
+        let mut cache = TranscriptCache::new(2);
+        cache.get(&sessions[0]);
+        cache.get(&sessions[1]);
+        cache.get(&sessions[0]); // a is now most recent
+        cache.get(&sessions[2]); // evicts b
+        assert_eq!(cache.len(), 2);
+
+        write_msg(&sessions[0].jsonl_path, "A");
+        write_msg(&sessions[1].jsonl_path, "B");
+        assert_eq!(cache.get(&sessions[0]).messages()[0].content, "a", "a still cached");
+        assert_eq!(cache.get(&sessions[1]).messages()[0].content, "B", "b was evicted");
+    }
+
+    #[test]
+    fn test_missing_file_yields_empty_transcript() {
+        let tmp = TempDir::new().unwrap();
+        let mut cache = TranscriptCache::default();
+        let tree = cache.get(&session_at(tmp.path().join("missing.jsonl")));
+        assert!(tree.messages().is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+use crate::models::Message;
+use std::collections::HashMap;
+
+/// Messages of one transcript arranged by their parent links.
+///
+/// Editing an earlier prompt or rewinding in Claude Code leaves the old turns in
+/// the JSONL and starts a new chain from an earlier message. Every leaf of the
+/// tree is one branch; the branch ending in the last written message is the active one.
+#[derive(Debug, Default)]
+pub struct ConversationTree {
+    messages: Vec<Message>,
+    parent: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    roots: Vec<usize>,
+    /// Leaf indices in file order; branch `n` ends in `leaves[n]`.
+    leaves: Vec<usize>,
+}
+
+/// Position of a message among the alternatives at a fork.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fork {
+    /// 1-based index of this message among its siblings.
+    pub position: usize,
+    pub count: usize,
+}
+
+impl ConversationTree {
+    pub fn build(messages: Vec<Message>) -> Self {
+        let by_uuid: HashMap<&str, usize> = messages
+            .iter()
+            .enumerate()
+            .filter_map(|(i, m)| m.uuid.as_deref().map(|u| (u, i)))
+            .collect();
+
+        // Ohne uuid oder mit unbekanntem Elternteil: linear an die vorherige Nachricht hängen
+        let parent: Vec<Option<usize>> = messages
+            .iter()
+            .enumerate()
+            .map(|(i, m)| match (&m.uuid, &m.parent_uuid) {
+                (_, Some(p)) => by_uuid
+                    .get(p.as_str())
+                    .copied()
+                    .filter(|&p| p < i)
+                    .or(i.checked_sub(1)),
+                (None, None) => i.checked_sub(1),
+                (Some(_), None) => None,
+            })
+            .collect();
+
+        let mut children = vec![Vec::new(); messages.len()];
+        let mut roots = Vec::new();
+        for (i, p) in parent.iter().enumerate() {
+            match p {
+                Some(p) => children[*p].push(i),
+                None => roots.push(i),
+            }
+        }
+        let leaves = (0..messages.len())
+            .filter(|&i| children[i].is_empty())
+            .collect();
+
+        Self {
+            messages,
+            parent,
+            children,
+            roots,
+            leaves,
+        }
+    }
+
+    /// All messages in file order, regardless of branch.
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    pub fn branch_count(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// The branch that ends in the most recently written message.
+    pub fn active_branch(&self) -> usize {
+        self.leaves.len().saturating_sub(1)
+    }
+
+    /// Message indices from the root to the leaf of `branch` (clamped to the last branch).
+    pub fn branch_path(&self, branch: usize) -> Vec<usize> {
+        let Some(&leaf) = self.leaves.get(branch.min(self.active_branch())) else {
+            return Vec::new();
+        };
+        let mut path = vec![leaf];
+        let mut current = leaf;
+        while let Some(p) = self.parent[current] {
+            path.push(p);
+            current = p;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Messages of `branch` in conversation order.
+    pub fn branch_messages(&self, branch: usize) -> Vec<&Message> {
+        self.branch_path(branch)
+            .into_iter()
+            .map(|i| &self.messages[i])
+            .collect()
+    }
+
+    /// If message `index` is one of several alternatives, where it sits among them.
+    pub fn fork_at(&self, index: usize) -> Option<Fork> {
+        let siblings = match self.parent[index] {
+            Some(p) => &self.children[p],
+            None => &self.roots,
+        };
+        if siblings.len() < 2 {
+            return None;
+        }
+        let position = siblings.iter().position(|&s| s == index)? + 1;
+        Some(Fork {
+            position,
+            count: siblings.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(uuid: &str, parent: Option<&str>, content: &str) -> Message {
+        Message {
+            role: "user".to_string(),
+            content: content.to_string(),
+            parts: Vec::new(),
+            timestamp: None,
+            uuid: Some(uuid.to_string()),
+            parent_uuid: parent.map(String::from),
+        }
+    }
+
+    fn contents(tree: &ConversationTree, branch: usize) -> Vec<&str> {
+        tree.branch_messages(branch)
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_linear_conversation_has_one_branch() {
+        let tree = ConversationTree::build(vec![
+            msg("a", None, "1"),
+            msg("b", Some("a"), "2"),
+            msg("c", Some("b"), "3"),
+        ]);
+        assert_eq!(tree.branch_count(), 1);
+        assert_eq!(contents(&tree, 0), vec!["1", "2", "3"]);
+        assert_eq!(tree.fork_at(1), None);
+    }
+
+    #[test]
+    fn test_edited_prompt_creates_sibling_branch() {
+        // b was edited into d; the newer branch d → e is active
+        let tree = ConversationTree::build(vec![
+            msg("a", None, "hi"),
+            msg("b", Some("a"), "old question"),
+            msg("c", Some("b"), "old answer"),
+            msg("d", Some("a"), "new question"),
+            msg("e", Some("d"), "new answer"),
+        ]);
+        assert_eq!(tree.branch_count(), 2);
+        assert_eq!(tree.active_branch(), 1);
+        assert_eq!(contents(&tree, 1), vec!["hi", "new question", "new answer"]);
+        assert_eq!(contents(&tree, 0), vec!["hi", "old question", "old answer"]);
+        assert_eq!(
+            tree.fork_at(3),
+            Some(Fork {
+                position: 2,
+                count: 2
+            })
+        );
+        // Out-of-range branch falls back to the active one
+        assert_eq!(contents(&tree, 9), contents(&tree, 1));
+    }
+
+    #[test]
+    fn test_edited_first_prompt_creates_second_root() {
+        let tree = ConversationTree::build(vec![
+            msg("a", None, "first try"),
+            msg("b", None, "second try"),
+        ]);
+        assert_eq!(tree.branch_count(), 2);
+        assert_eq!(contents(&tree, tree.active_branch()), vec!["second try"]);
+        assert_eq!(tree.fork_at(0).unwrap().count, 2);
+    }
+
+    #[test]
+    fn test_messages_without_links_are_chained() {
+        let mut a = msg("a", None, "1");
+        let mut b = msg("b", None, "2");
+        a.uuid = None;
+        b.uuid = None;
+        let tree = ConversationTree::build(vec![a, b]);
+        assert_eq!(tree.branch_count(), 1);
+        assert_eq!(contents(&tree, 0), vec!["1", "2"]);
+    }
+
+    #[test]
+    fn test_empty_tree() {
+        let tree = ConversationTree::build(Vec::new());
+        assert_eq!(tree.branch_count(), 0);
+        assert!(tree.branch_messages(0).is_empty());
+    }
+}
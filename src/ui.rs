@@ -1,15 +1,26 @@
-use crate::app::{App, ClickAction, FocusPanel, Tab};
+use crate::app::{App, ClickAction, ConflictOperation, FocusPanel, Tab};
+use crate::export::ExportFormat;
+use crate::models::{format_duration, format_size, format_timestamp, format_tokens, MessagePart};
+use crate::pricing::format_cost;
+use crate::store::{ConflictPolicy, LoadProgress};
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Table, Wrap},
     Frame,
 };
 
-pub fn draw_loading(f: &mut Frame, loaded: usize, total: usize) {
+/// Text blocks longer than this are cut off in the preview.
+const PREVIEW_PART_MAX_BYTES: usize = 500;
+/// Listenbreite (mit Rahmen), ab der Tokens und Kosten neben Datum und Name passen.
+const LIST_USAGE_MIN_WIDTH: u16 = 2 + 10 + 8 + 16 + 7 + 7 + 8 + 5;
+
+pub fn draw_loading(f: &mut Frame, progress: &LoadProgress) {
     let area = f.area();
-    let percent = if total > 0 { (loaded * 100) / total } else { 0 };
+    let percent = (progress.loaded * 100)
+        .checked_div(progress.total)
+        .unwrap_or(0);
 
     let gauge = ratatui::widgets::Gauge::default()
         .block(
@@ -20,27 +31,44 @@ pub fn draw_loading(f: &mut Frame, loaded: usize, total: usize) {
         )
         .gauge_style(Style::default().fg(Color::Green).bg(Color::Black))
         .percent(percent as u16)
-        .label(format!("{}/{}", loaded, total));
+        .label(format!("{}/{}", progress.loaded, progress.total));
+
+    let mut stats = vec![Span::styled(
+        format!("{} parsed", format_size(progress.bytes)),
+        Style::default().fg(Color::DarkGray),
+    )];
+    if progress.failed > 0 {
+        stats.push(Span::styled(" · ", Style::default().fg(Color::DarkGray)));
+        stats.push(Span::styled(
+            format!("{} failed", progress.failed),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    let stats_line = Paragraph::new(Line::from(stats)).alignment(Alignment::Center);
 
     let centered = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Percentage(45),
             Constraint::Length(3),
+            Constraint::Length(1),
             Constraint::Percentage(45),
         ])
         .split(area);
 
-    let horizontal = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(20),
-            Constraint::Percentage(60),
-            Constraint::Percentage(20),
-        ])
-        .split(centered[1]);
+    let horizontal = |row: Rect| {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(20),
+                Constraint::Percentage(60),
+                Constraint::Percentage(20),
+            ])
+            .split(row)[1]
+    };
 
-    f.render_widget(gauge, horizontal[1]);
+    f.render_widget(gauge, horizontal(centered[1]));
+    f.render_widget(stats_line, horizontal(centered[2]));
 }
 
 pub fn draw(f: &mut Frame, app: &mut App) {
@@ -69,8 +97,12 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         .constraints([Constraint::Percentage(40), Constraint::Min(0)])
         .split(chunks[1]);
 
-    draw_list(f, content_chunks[0], app);
-    draw_preview(f, content_chunks[1], app);
+    if app.current_tab == Tab::Stats {
+        draw_stats(f, chunks[1], app);
+    } else {
+        draw_list(f, content_chunks[0], app);
+        draw_preview(f, content_chunks[1], app);
+    }
 
     draw_commands(f, chunks[2], app);
 
@@ -89,6 +121,166 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     if app.show_rename {
         draw_rename_modal(f, app);
     }
+
+    if app.show_export_picker {
+        draw_export_picker(f, app);
+    }
+
+    if app.show_secret_scan {
+        draw_secret_scan(f, app);
+    }
+
+    // Zuletzt: der Konflikt kann aus dem Secret-Scan heraus entstehen
+    if app.conflict.is_some() {
+        draw_conflict_modal(f, app);
+    }
+}
+
+/// Dashboard des Stats-Tabs: Summen, Projekte, Aktivität, Uhrzeiten und größte Sessions.
+fn draw_stats(f: &mut Frame, area: Rect, app: &mut App) {
+    let disk = app.disk_usage;
+    let stats = app.stats();
+    let block = |title: &'static str| {
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray))
+    };
+    let label = |text: &'static str| Span::styled(text, Style::default().fg(Color::Yellow));
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(40),
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+        ])
+        .split(area);
+    let split = |row: Rect, left: u16| {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(left), Constraint::Min(0)])
+            .split(row)
+    };
+    let top = split(rows[0], 40);
+    let middle = split(rows[1], 50);
+    let bottom = split(rows[2], 55);
+
+    // Summen und Speicherbelegung
+    let disk_total = disk.projects + disk.trash;
+    let trash_share = (disk.trash * 100).checked_div(disk_total).unwrap_or(0);
+    let busiest = stats
+        .busiest_hour()
+        .map(|h| format!("{:02}:00–{:02}:00", h, h + 1))
+        .unwrap_or_else(|| "—".to_string());
+    let totals = vec![
+        Line::from(vec![label("Sessions:  "), Span::raw(stats.sessions.to_string())]),
+        Line::from(vec![label("Messages:  "), Span::raw(stats.messages.to_string())]),
+        Line::from(vec![label("Tokens:    "), Span::raw(format_tokens(stats.tokens))]),
+        Line::from(vec![
+            label("Cost:      "),
+            Span::raw(format!("~{}", format_cost(stats.cost))),
+        ]),
+        Line::from(vec![label("Busiest:   "), Span::raw(busiest)]),
+        Line::from(""),
+        Line::from(vec![label("Projects:  "), Span::raw(format_size(disk.projects))]),
+        Line::from(vec![
+            label("Trash:     "),
+            Span::raw(format_size(disk.trash)),
+            Span::styled(
+                format!("  ({}% of {})", trash_share, format_size(disk_total)),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]),
+    ];
+    f.render_widget(Paragraph::new(totals).block(block(" Totals ")), top[0]);
+
+    let header = Row::new(vec!["Project", "Sessions", "Msgs", "Size"]).style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    );
+    let project_rows: Vec<Row> = stats
+        .projects
+        .iter()
+        .map(|p| {
+            Row::new(vec![
+                Cell::from(p.name.clone()),
+                Cell::from(p.sessions.to_string()),
+                Cell::from(p.messages.to_string()),
+                Cell::from(format_size(p.size)),
+            ])
+        })
+        .collect();
+    let projects = Table::new(
+        project_rows,
+        [
+            Constraint::Min(10),
+            Constraint::Length(8),
+            Constraint::Length(7),
+            Constraint::Length(9),
+        ],
+    )
+    .header(header)
+    .block(block(" Projects "));
+    f.render_widget(projects, top[1]);
+
+    // Aktivität: Sessions pro Tag (Sparkline) und pro Woche (Balken)
+    let daily = ratatui::widgets::Sparkline::default()
+        .block(block(" Sessions per day (30 days) "))
+        .data(&stats.daily)
+        .style(Style::default().fg(Color::Green));
+    f.render_widget(daily, middle[0]);
+
+    let weekly_labels: Vec<String> = (0..stats.weekly.len())
+        .map(|i| match stats.weekly.len() - 1 - i {
+            0 => "now".to_string(),
+            ago => format!("-{}", ago),
+        })
+        .collect();
+    let weekly_data: Vec<(&str, u64)> = weekly_labels
+        .iter()
+        .map(String::as_str)
+        .zip(stats.weekly.iter().copied())
+        .collect();
+    let weekly = ratatui::widgets::BarChart::default()
+        .block(block(" Sessions per week "))
+        .data(&weekly_data)
+        .bar_width(3)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::Cyan))
+        .value_style(Style::default().fg(Color::Black).bg(Color::Cyan));
+    f.render_widget(weekly, middle[1]);
+
+    // Uhrzeiten (lokale Zeit des Session-Starts)
+    let hour_labels: Vec<String> = (0..24)
+        .map(|h| if h % 6 == 0 { format!("{:02}", h) } else { String::new() })
+        .collect();
+    let hour_data: Vec<(&str, u64)> = hour_labels
+        .iter()
+        .map(String::as_str)
+        .zip(stats.hours.iter().copied())
+        .collect();
+    let hours = ratatui::widgets::BarChart::default()
+        .block(block(" Busiest hours "))
+        .data(&hour_data)
+        .bar_width(2)
+        .bar_gap(0)
+        .bar_style(Style::default().fg(Color::Magenta))
+        .value_style(Style::default().fg(Color::Black).bg(Color::Magenta));
+    f.render_widget(hours, bottom[0]);
+
+    let largest: Vec<Line> = stats
+        .largest
+        .iter()
+        .map(|(name, size)| {
+            Line::from(vec![
+                Span::styled(format!("{:>9}  ", format_size(*size)), Style::default().fg(Color::Cyan)),
+                Span::raw(sanitize_for_display(name)),
+            ])
+        })
+        .collect();
+    f.render_widget(Paragraph::new(largest).block(block(" Largest sessions ")), bottom[1]);
 }
 
 fn draw_tabs(f: &mut Frame, area: Rect, app: &mut App) {
@@ -104,14 +296,17 @@ fn draw_tabs(f: &mut Frame, area: Rect, app: &mut App) {
     };
     let (sessions_marker, sessions_style) = tab_indicator(Tab::Sessions);
     let (trash_marker, trash_style) = tab_indicator(Tab::Trash);
+    let (stats_marker, stats_style) = tab_indicator(Tab::Stats);
 
     let sessions_text = format!("  {} 1 Sessions ({})  ", sessions_marker, session_count);
     let trash_text = format!("  {} 2 Trash ({})  ", trash_marker, trash_count);
+    let stats_text = format!("  {} 3 Stats  ", stats_marker);
     let help_text = "│  h help  ";
 
     let tabs = vec![
         Span::styled(&sessions_text, sessions_style),
         Span::styled(&trash_text, trash_style),
+        Span::styled(&stats_text, stats_style),
         Span::styled(help_text, Style::default().fg(Color::DarkGray)),
     ];
 
@@ -130,6 +325,7 @@ fn draw_tabs(f: &mut Frame, area: Rect, app: &mut App) {
     let content_x = area.x + 1;
     let sw = sessions_text.chars().count() as u16;
     let tw = trash_text.chars().count() as u16;
+    let stw = stats_text.chars().count() as u16;
     let hw = help_text.chars().count() as u16;
     app.click_regions.push((
         Rect { x: content_x, y: area.y, width: sw, height: area.height },
@@ -140,7 +336,11 @@ fn draw_tabs(f: &mut Frame, area: Rect, app: &mut App) {
         ClickAction::SwitchTab(Tab::Trash),
     ));
     app.click_regions.push((
-        Rect { x: content_x + sw + tw, y: area.y, width: hw, height: area.height },
+        Rect { x: content_x + sw + tw, y: area.y, width: stw, height: area.height },
+        ClickAction::SwitchTab(Tab::Stats),
+    ));
+    app.click_regions.push((
+        Rect { x: content_x + sw + tw + stw, y: area.y, width: hw, height: area.height },
         ClickAction::ToggleHelp,
     ));
 }
@@ -167,41 +367,86 @@ fn draw_list(f: &mut Frame, area: Rect, app: &mut App) {
         Cell::from(text).style(header_style)
     };
 
-    let header = Row::new(vec![
+    // Checkbox-Spalte nur, solange Sessions markiert sind
+    let marked_count = filtered.iter().filter(|s| app.marked.contains(&s.id)).count();
+    let marking = marked_count > 0;
+    // In schmalen Listen verdrängen Tokens und Kosten sonst Datum und Sortierpfeil
+    let checkbox_width = if marking { 4 } else { 0 };
+    let show_usage = area.width >= LIST_USAGE_MIN_WIDTH + checkbox_width;
+
+    let mut header_cells = vec![
         make_header("Project", crate::app::SortField::Project),
         make_header("Name", crate::app::SortField::Name),
         make_header("Date", crate::app::SortField::Date),
         make_header("Msgs", crate::app::SortField::Messages),
-    ])
-    .bottom_margin(0);
+    ];
+    if show_usage {
+        header_cells.push(make_header("Tokens", crate::app::SortField::Tokens));
+        header_cells.push(make_header("Cost", crate::app::SortField::Cost));
+    }
+    if marking {
+        header_cells.insert(0, Cell::from(""));
+    }
+    let header = Row::new(header_cells).bottom_margin(0);
 
     let rows: Vec<Row> = filtered
         .iter()
         .map(|session| {
-            let formatted_date = format_datetime(&session.updated_at);
+            let formatted_date = format_datetime(session.list_date());
             let name = session.slug.as_deref().unwrap_or("");
 
-            Row::new(vec![
-                Cell::from(session.display_project_name()),
+            // Markierung aus dem Secret-Scan
+            let project = if app.has_secrets(session) {
+                Cell::from(Line::from(vec![
+                    Span::styled("⚠ ", Style::default().fg(Color::Yellow)),
+                    Span::raw(session.display_project_name()),
+                ]))
+            } else {
+                Cell::from(session.display_project_name())
+            };
+
+            let mut cells = vec![
+                project,
                 Cell::from(name),
                 Cell::from(formatted_date),
-                Cell::from(format!("{}", session.messages.len())),
-            ])
-            .style(Style::default().fg(Color::White))
+                Cell::from(format!("{}", session.message_count)),
+            ];
+            if show_usage {
+                cells.push(Cell::from(format_tokens(session.total_tokens())));
+                cells.push(Cell::from(format_cost(app.session_cost(session))));
+            }
+            let is_marked = app.marked.contains(&session.id);
+            if marking {
+                let checkbox = if is_marked { "[x]" } else { "[ ]" };
+                cells.insert(0, Cell::from(checkbox));
+            }
+            let fg = if is_marked { Color::Cyan } else { Color::White };
+            Row::new(cells).style(Style::default().fg(fg))
         })
         .collect();
 
-    let title = match app.current_tab {
-        Tab::Sessions => format!(" Sessions ({}) ", filtered.len()),
-        Tab::Trash => format!(" Trash ({}) ", filtered.len()),
+    let list_name = match app.current_tab {
+        Tab::Sessions | Tab::Stats => "Sessions",
+        Tab::Trash => "Trash",
+    };
+    let title = if marking {
+        format!(" {} ({}, {} marked) ", list_name, filtered.len(), marked_count)
+    } else {
+        format!(" {} ({}) ", list_name, filtered.len())
     };
 
-    let widths = [
+    let mut widths = vec![
         Constraint::Min(10),
         Constraint::Min(8),
         Constraint::Length(16),
         Constraint::Length(7),
     ];
+    if show_usage {
+        widths.extend([Constraint::Length(7), Constraint::Length(8)]);
+    }
+    if marking {
+        widths.insert(0, Constraint::Length(3));
+    }
 
     let table = Table::new(rows, widths)
         .header(header)
@@ -243,7 +488,7 @@ fn draw_list(f: &mut Frame, area: Rect, app: &mut App) {
     );
 }
 
-fn format_datetime(iso_string: &str) -> String {
+pub fn format_datetime(iso_string: &str) -> String {
     if iso_string.len() >= 16 {
         let date_part = &iso_string[0..10];
         let time_part = &iso_string[11..16];
@@ -253,77 +498,153 @@ fn format_datetime(iso_string: &str) -> String {
     }
 }
 
-fn draw_preview(f: &mut Frame, area: Rect, app: &App) {
-    if let Some(session) = app.get_selected_session() {
+fn draw_preview(f: &mut Frame, area: Rect, app: &mut App) {
+    if let Some(session) = app.get_selected_session().cloned() {
+        // Transkript erst bei Anzeige laden (LRU-Cache im App-State)
+        let tree = app.transcripts.get(&session);
+        let branch = app
+            .selected_branch(&session.id)
+            .unwrap_or_else(|| tree.active_branch());
+        let label = |text: &'static str| Span::styled(text, Style::default().fg(Color::Yellow));
+
         let mut lines = vec![
-            Line::from(vec![
-                Span::styled("Project: ", Style::default().fg(Color::Yellow)),
-                Span::raw(session.display_project_name()),
-            ]),
-            Line::from(vec![
-                Span::styled("Session: ", Style::default().fg(Color::Yellow)),
-                Span::raw(&session.id),
-            ]),
+            Line::from(vec![label("Project: "), Span::raw(session.display_project_name())]),
+            Line::from(vec![label("Session: "), Span::raw(&session.id)]),
             Line::from(vec![
                 Span::styled("Label:   ", Style::default().fg(Color::Cyan)),
                 Span::raw(session.slug.as_deref().unwrap_or("—")),
             ]),
+            Line::from(vec![label("Updated: "), Span::raw(&session.updated_at)]),
+        ];
+        // Herkunft aus dem Sidecar im Papierkorb
+        if let Some(info) = &session.trashed {
+            lines.extend([
+                Line::from(vec![
+                    label("Deleted: "),
+                    Span::raw(format_datetime(&info.deleted_at)),
+                    Span::styled(
+                        format!("  ({})", info.reason.label()),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]),
+                Line::from(vec![
+                    label("From:    "),
+                    Span::raw(info.original_path.display().to_string()),
+                ]),
+                Line::from(vec![label("Title:   "), Span::raw(info.title.clone())]),
+            ]);
+        }
+        // Dauer aus erster/letzter Nachricht, nicht aus Datei-Zeitstempeln
+        if let Some(duration) = session.duration() {
+            lines.push(Line::from(vec![
+                label("Duration: "),
+                Span::raw(format_duration(duration)),
+            ]));
+        }
+        lines.extend([
+            Line::from(vec![label("Size: "), Span::raw(format_size(session.size))]),
             Line::from(vec![
-                Span::styled("Updated: ", Style::default().fg(Color::Yellow)),
-                Span::raw(&session.updated_at),
-            ]),
-            Line::from(vec![
-                Span::styled("Size: ", Style::default().fg(Color::Yellow)),
-                Span::raw(format_size(session.size)),
-            ]),
-            Line::from(vec![
-                Span::styled("Messages: ", Style::default().fg(Color::Yellow)),
-                Span::raw(format!("{}", session.messages.len())),
+                label("Messages: "),
+                Span::raw(format!("{}", session.message_count)),
             ]),
             Line::from(vec![
-                Span::styled("Entries: ", Style::default().fg(Color::Yellow)),
+                label("Entries: "),
                 Span::raw(format!("{}", session.total_entries)),
             ]),
+        ]);
+        if !session.token_usage.is_empty() {
+            lines.push(Line::from(vec![
+                label("Tokens: "),
+                Span::raw(format_tokens(session.total_tokens())),
+            ]));
+            lines.push(Line::from(vec![
+                label("Cost:   "),
+                Span::raw(format!("~{}", format_cost(app.session_cost(&session)))),
+                Span::styled(
+                    format!(
+                        "  (project: ~{})",
+                        format_cost(app.project_cost(&session.project_path))
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+            for (model, usage) in &session.token_usage {
+                let cost = app
+                    .config
+                    .pricing
+                    .for_model(model)
+                    .map(|p| format_cost(p.cost(usage)))
+                    .unwrap_or_else(|| "no price".to_string());
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {}: ", model), Style::default().fg(Color::Cyan)),
+                    Span::raw(format!("{} · {}", format_tokens(usage.total()), cost)),
+                    Span::styled(
+                        format!(
+                            "  in {} · out {} · cache write {} · cache read {}",
+                            format_tokens(usage.input),
+                            format_tokens(usage.output),
+                            format_tokens(usage.cache_creation),
+                            format_tokens(usage.cache_read)
+                        ),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]));
+            }
+        }
+        if tree.branch_count() > 1 {
+            lines.push(Line::from(vec![
+                label("Branch: "),
+                Span::raw(format!("{}/{}", branch + 1, tree.branch_count())),
+                Span::styled("  [ ] to switch", Style::default().fg(Color::DarkGray)),
+            ]));
+        }
+        lines.extend([
             Line::from(""),
             Line::from(Span::styled(
                 "─── Conversation ───",
                 Style::default().fg(Color::DarkGray),
             )),
             Line::from(""),
-        ];
-
-        for msg in &session.messages {
-            let (prefix, style) = if msg.role == "user" {
-                (
-                    "▶ You: ",
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
-                )
-            } else {
-                (
-                    "◀ Agent: ",
-                    Style::default()
-                        .fg(Color::Green)
-                        .add_modifier(Modifier::BOLD),
-                )
-            };
-
-            lines.push(Line::from(Span::styled(prefix, style)));
+        ]);
 
-            let sanitized = sanitize_for_display(&msg.content);
-            let truncated = if sanitized.len() > 500 {
-                let mut end = 500;
-                while !sanitized.is_char_boundary(end) {
-                    end -= 1;
+        for index in tree.branch_path(branch) {
+            let msg = &tree.messages()[index];
+            // Gabelung: diese Nachricht ist eine von mehreren Alternativen
+            if let Some(fork) = tree.fork_at(index) {
+                lines.push(Line::from(Span::styled(
+                    format!("  ── alternative {}/{} ──", fork.position, fork.count),
+                    Style::default().fg(Color::Magenta),
+                )));
+            }
+            // Reine Tool-Ergebnisse hängen optisch am vorherigen Tool-Aufruf, ohne eigenen Sprecher
+            if !msg.is_tool_output() {
+                let (prefix, style) = if msg.role == "user" {
+                    (
+                        "▶ You: ",
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    (
+                        "◀ Agent: ",
+                        Style::default()
+                            .fg(Color::Green)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                };
+                let mut speaker = vec![Span::styled(prefix, style)];
+                if let Some(ts) = &msg.timestamp {
+                    speaker.push(Span::styled(
+                        format_timestamp(ts),
+                        Style::default().fg(Color::DarkGray),
+                    ));
                 }
-                format!("{}...", &sanitized[..end])
-            } else {
-                sanitized
-            };
+                lines.push(Line::from(speaker));
+            }
 
-            for text_line in truncated.lines() {
-                lines.push(Line::from(format!("  {}", text_line)));
+            for part in &msg.parts {
+                push_part_lines(&mut lines, part, app.show_tool_output);
             }
             lines.push(Line::from(""));
         }
@@ -373,6 +694,81 @@ fn draw_preview(f: &mut Frame, area: Rect, app: &App) {
     }
 }
 
+/// Shortens preview text to `PREVIEW_PART_MAX_BYTES` on a char boundary.
+fn truncate_for_preview(text: &str) -> String {
+    let sanitized = sanitize_for_display(text);
+    if sanitized.len() > PREVIEW_PART_MAX_BYTES {
+        let mut end = PREVIEW_PART_MAX_BYTES;
+        while !sanitized.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}...", &sanitized[..end])
+    } else {
+        sanitized
+    }
+}
+
+/// Renders one message part; tool output is only shown when `show_tool_output` is set.
+fn push_part_lines(lines: &mut Vec<Line>, part: &MessagePart, show_tool_output: bool) {
+    let dim = Style::default().fg(Color::DarkGray);
+    match part {
+        MessagePart::Text { text } => {
+            for text_line in truncate_for_preview(text).lines() {
+                lines.push(Line::from(format!("  {}", text_line)));
+            }
+        }
+        MessagePart::Thinking { text } => {
+            lines.push(Line::from(Span::styled(
+                "  ∴ thinking",
+                dim.add_modifier(Modifier::ITALIC),
+            )));
+            for text_line in truncate_for_preview(text).lines() {
+                lines.push(Line::from(Span::styled(
+                    format!("    {}", text_line),
+                    dim.add_modifier(Modifier::ITALIC),
+                )));
+            }
+        }
+        MessagePart::ToolUse { name, input, .. } => {
+            let summary = MessagePart::tool_input_summary(input);
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  ▸ {}", name),
+                    Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" "),
+                Span::raw(truncate_for_preview(&summary).lines().next().unwrap_or("").to_string()),
+            ]));
+        }
+        MessagePart::ToolResult {
+            is_error, output, ..
+        } => {
+            let (label, color) = if *is_error {
+                ("error", Color::Red)
+            } else {
+                ("output", Color::DarkGray)
+            };
+            let line_count = output.lines().count();
+            if show_tool_output {
+                lines.push(Line::from(Span::styled(
+                    format!("  ◂ {} ({} lines)", label, line_count),
+                    Style::default().fg(color),
+                )));
+                for text_line in truncate_for_preview(output).lines() {
+                    lines.push(Line::from(Span::styled(format!("    {}", text_line), dim)));
+                }
+            } else {
+                lines.push(Line::from(Span::styled(
+                    format!("  ◂ {} ({} lines, o to expand)", label, line_count),
+                    Style::default().fg(color),
+                )));
+            }
+        }
+    }
+}
+
 fn draw_search_modal(f: &mut Frame, app: &mut App) {
     let size = f.area();
     let area = Rect {
@@ -387,7 +783,7 @@ fn draw_search_modal(f: &mut Frame, app: &mut App) {
     let search = Paragraph::new(format!("Search: {}_", app.search_query))
         .block(
             Block::default()
-                .title(" Search (Esc to close) ")
+                .title(" Search (Enter: full text, Esc to close) ")
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Color::Cyan)),
         )
@@ -480,13 +876,18 @@ fn draw_commands(f: &mut Frame, area: Rect, app: &mut App) {
             ("q", " quit", ClickAction::Quit),
         ],
         Tab::Trash => vec![
-            ("u", " undo  ", ClickAction::RestoreFromTrash),
+            ("r", " restore  ", ClickAction::RestoreFromTrash),
             ("e", " empty trash ", ClickAction::EmptyTrash),
             ("f", " find  ", ClickAction::ToggleSearch),
             ("s", " sort  ", ClickAction::ToggleSort),
             ("h", " help  ", ClickAction::ToggleHelp),
             ("q", " quit", ClickAction::Quit),
         ],
+        Tab::Stats => vec![
+            ("1", " sessions  ", ClickAction::SwitchTab(Tab::Sessions)),
+            ("h", " help  ", ClickAction::ToggleHelp),
+            ("q", " quit", ClickAction::Quit),
+        ],
     };
 
     // Build spans for rendering and click regions simultaneously
@@ -537,16 +938,6 @@ fn sanitize_for_display(text: &str) -> String {
         .collect()
 }
 
-fn format_size(bytes: u64) -> String {
-    if bytes < 1024 {
-        format!("{} B", bytes)
-    } else if bytes < 1024 * 1024 {
-        format!("{:.1} KB", bytes as f64 / 1024.0)
-    } else {
-        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
-    }
-}
-
 fn draw_help_modal(f: &mut Frame, app: &mut App) {
     let area = f.area();
     let width = (area.width as f32 * 0.8).min(100.0) as u16;
@@ -588,26 +979,119 @@ fn draw_help_modal(f: &mut Frame, app: &mut App) {
     app.click_regions.clear();
 }
 
+/// Fundliste des Secret-Scans: oben die betroffenen Sessions, darunter die
+/// Fundstellen der ausgewählten Session (Nachricht, Detektor, maskierter Ausschnitt).
+fn draw_secret_scan(f: &mut Frame, app: &mut App) {
+    let area = f.area();
+    let width = (area.width as f32 * 0.8).min(110.0) as u16;
+    // Statuszeile frei lassen, dort erscheint die Bestätigung
+    let height = (area.height.saturating_sub(3) as f32 * 0.9) as u16;
+    let popup_area = Rect {
+        x: (area.width.saturating_sub(width)) / 2,
+        y: (area.height.saturating_sub(3).saturating_sub(height)) / 2,
+        width,
+        height,
+    };
+    let block = Block::default()
+        .title(" Secret scan ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(popup_area);
+
+    let flagged = app.flagged_sessions();
+    let list_height = (flagged.len() as u16).min(inner.height / 3).max(1) as usize;
+    let offset = (app.secret_scan_idx + 1).saturating_sub(list_height);
+
+    let mut text = Vec::new();
+    for (i, session) in flagged.iter().enumerate().skip(offset).take(list_height) {
+        let selected = i == app.secret_scan_idx;
+        let style = if selected {
+            Style::default().fg(Color::Black).bg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        text.push(Line::from(vec![
+            Span::styled(
+                if selected { " ▸ " } else { "   " },
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(
+                format!(
+                    "{}  ({} finding(s){})",
+                    sanitize_for_display(&session.display_name()),
+                    app.secret_findings_for(session).len(),
+                    if app.is_in_trash(session) { ", in trash" } else { "" }
+                ),
+                style,
+            ),
+        ]));
+    }
+    text.push(Line::from(""));
+
+    // Fundstellen der ausgewählten Session; was nicht passt, wird gezählt
+    let findings = flagged
+        .get(app.secret_scan_idx)
+        .map(|s| app.secret_findings_for(s))
+        .unwrap_or_default();
+    let mut room = (inner.height as usize).saturating_sub(text.len() + 2);
+    if findings.len() > room {
+        room = room.saturating_sub(1);
+    }
+    for finding in findings.iter().take(room) {
+        text.push(Line::from(vec![
+            Span::styled(
+                format!("   #{:<4} ", finding.message_index + 1),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(format!("{:<15} ", finding.detector), Style::default().fg(Color::Red)),
+            Span::raw(sanitize_for_display(&finding.snippet)),
+        ]));
+    }
+    if findings.len() > room {
+        text.push(Line::from(Span::styled(
+            format!("   … {} more", findings.len() - room),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(vec![
+        Span::styled("  [s]", Style::default().fg(Color::Green)),
+        Span::raw(" scrub  "),
+        Span::styled("[d]", Style::default().fg(Color::Red)),
+        Span::raw(" trash  "),
+        Span::styled("[Esc]", Style::default().fg(Color::Cyan)),
+        Span::raw(" close"),
+    ]));
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(block, popup_area);
+    f.render_widget(Paragraph::new(text), inner);
+
+    // Nur die [y]/[n]-Buttons einer laufenden Bestätigung bleiben klickbar
+    app.click_regions
+        .retain(|(_, a)| matches!(a, ClickAction::ConfirmYes | ClickAction::ConfirmNo));
+}
+
 fn parse_markdown_line(line: &str) -> Line<'_> {
     let line = sanitize_for_display(line);
 
-    if line.starts_with("# ") {
+    if let Some(rest) = line.strip_prefix("# ") {
         Line::from(vec![Span::styled(
-            line[2..].to_string(),
+            rest.to_string(),
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
         )])
-    } else if line.starts_with("## ") {
+    } else if let Some(rest) = line.strip_prefix("## ") {
         Line::from(vec![Span::styled(
-            line[3..].to_string(),
+            rest.to_string(),
             Style::default()
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
         )])
-    } else if line.starts_with("### ") {
+    } else if let Some(rest) = line.strip_prefix("### ") {
         Line::from(vec![Span::styled(
-            line[4..].to_string(),
+            rest.to_string(),
             Style::default()
                 .fg(Color::Green)
                 .add_modifier(Modifier::BOLD),
@@ -660,7 +1144,7 @@ fn parse_inline_formatting(text: String, base_style: Style) -> Vec<Span<'static>
                 current_text.clear();
             }
             let mut code_content = String::new();
-            while let Some(next_ch) = chars.next() {
+            for next_ch in chars.by_ref() {
                 if next_ch == '`' {
                     break;
                 }
@@ -702,7 +1186,7 @@ fn parse_inline_formatting(text: String, base_style: Style) -> Vec<Span<'static>
         } else if ch == '[' {
             let mut link_text = String::new();
             let mut found_end = false;
-            while let Some(next_ch) = chars.next() {
+            for next_ch in chars.by_ref() {
                 if next_ch == ']' {
                     found_end = true;
                     break;
@@ -712,7 +1196,7 @@ fn parse_inline_formatting(text: String, base_style: Style) -> Vec<Span<'static>
             if found_end && chars.peek() == Some(&'(') {
                 chars.next();
                 let mut url = String::new();
-                while let Some(next_ch) = chars.next() {
+                for next_ch in chars.by_ref() {
                     if next_ch == ')' {
                         break;
                     }
@@ -820,8 +1304,12 @@ fn draw_rename_modal(f: &mut Frame, app: &mut App) {
         height: height.min(area.height),
     };
 
+    let title = match app.marked_sessions().len() {
+        0 | 1 => " Rename Session ".to_string(),
+        count => format!(" Rename {} Sessions ", count),
+    };
     let block = Block::default()
-        .title(" Rename Session ")
+        .title(title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
 
@@ -852,6 +1340,167 @@ fn draw_rename_modal(f: &mut Frame, app: &mut App) {
     f.render_widget(Paragraph::new(text), inner);
 }
 
+fn draw_export_picker(f: &mut Frame, app: &mut App) {
+    let area = f.area();
+    let width = 44u16;
+    let height = ExportFormat::ALL.len() as u16 + 8;
+
+    let popup_area = Rect {
+        x: (area.width.saturating_sub(width)) / 2,
+        y: (area.height.saturating_sub(height)) / 2,
+        width: width.min(area.width),
+        height: height.min(area.height),
+    };
+
+    let block = Block::default()
+        .title(" Export as ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(popup_area);
+
+    let mut text = vec![Line::from("")];
+    for (i, format) in ExportFormat::ALL.iter().enumerate() {
+        let selected = i == app.export_picker_idx;
+        let style = if selected {
+            Style::default().fg(Color::Black).bg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let marker = if selected { "▸" } else { " " };
+        text.push(Line::from(vec![
+            Span::styled(format!(" {}{} ", marker, i + 1), Style::default().fg(Color::Yellow)),
+            Span::styled(
+                format!(" {:<12} .{:<5}", format.label(), format.exporter().extension()),
+                style,
+            ),
+        ]));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(vec![
+        Span::styled("  [Tab] ", Style::default().fg(Color::Yellow)),
+        Span::styled(app.export_scope_label(), Style::default().fg(Color::Cyan)),
+    ]));
+    text.push(Line::from(vec![
+        Span::styled("  [r]   ", Style::default().fg(Color::Yellow)),
+        Span::raw("Redact secrets: "),
+        if app.config.redact_secrets {
+            Span::styled("on", Style::default().fg(Color::Green))
+        } else {
+            Span::styled("off", Style::default().fg(Color::DarkGray))
+        },
+    ]));
+    text.push(Line::from(""));
+    text.push(Line::from(vec![
+        Span::styled("  [Enter]", Style::default().fg(Color::Green)),
+        Span::raw(" export  "),
+        Span::styled("[Esc]", Style::default().fg(Color::Red)),
+        Span::raw(" cancel"),
+    ]));
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(block, popup_area);
+    f.render_widget(Paragraph::new(text), inner);
+
+    // Click-Regionen: nur die Formatzeilen, der Picker überdeckt alles andere
+    app.click_regions.clear();
+    for (i, format) in ExportFormat::ALL.iter().enumerate() {
+        let row = Rect {
+            x: inner.x,
+            y: inner.y + 1 + i as u16,
+            width: inner.width,
+            height: 1,
+        };
+        app.click_regions.push((row.intersection(inner), ClickAction::ExportAs(*format)));
+    }
+    let scope_row = Rect {
+        x: inner.x,
+        y: inner.y + 2 + ExportFormat::ALL.len() as u16,
+        width: inner.width,
+        height: 1,
+    };
+    app.click_regions.push((scope_row.intersection(inner), ClickAction::CycleExportScope));
+    let redact_row = Rect {
+        y: scope_row.y + 1,
+        ..scope_row
+    };
+    app.click_regions.push((redact_row.intersection(inner), ClickAction::ToggleRedaction));
+}
+
+/// Tasten im Konflikt-Modal, in der Reihenfolge von `ConflictPolicy::RESOLUTIONS`.
+pub const CONFLICT_KEYS: [char; 3] = ['k', 'o', 'm'];
+
+fn draw_conflict_modal(f: &mut Frame, app: &mut App) {
+    let Some(conflict) = app.conflict.clone() else {
+        return;
+    };
+    let area = f.area();
+    let width = 64u16;
+    let height = 11u16;
+    let popup_area = Rect {
+        x: (area.width.saturating_sub(width)) / 2,
+        y: (area.height.saturating_sub(height)) / 2,
+        width: width.min(area.width),
+        height: height.min(area.height),
+    };
+
+    let block = Block::default()
+        .title(" Already exists ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(popup_area);
+
+    let question = match conflict.operation {
+        ConflictOperation::Restore => format!("Restore {} over", conflict.session_id),
+        ConflictOperation::Trash(_) => format!("Move {} to trash over", conflict.session_id),
+    };
+    // Lange Pfade von links kürzen, der Dateiname ist das Wichtigste
+    let path = conflict.existing.display().to_string();
+    let max = inner.width.saturating_sub(4) as usize;
+    let chars = path.chars().count();
+    let path = if chars > max {
+        let tail: String = path.chars().skip(chars + 1 - max).collect();
+        format!("…{}", tail)
+    } else {
+        path
+    };
+
+    let mut text = vec![
+        Line::from(""),
+        Line::from(format!("  {}", question)),
+        Line::from(Span::styled(format!("  {}", path), Style::default().fg(Color::Cyan))),
+        Line::from(""),
+    ];
+    for (resolution, key) in ConflictPolicy::RESOLUTIONS.iter().zip(CONFLICT_KEYS) {
+        text.push(Line::from(vec![
+            Span::styled(format!("  [{}] ", key), Style::default().fg(Color::Yellow)),
+            Span::raw(resolution.label()),
+        ]));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(vec![
+        Span::styled("  [Esc]", Style::default().fg(Color::Red)),
+        Span::raw(" cancel"),
+    ]));
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(block, popup_area);
+    f.render_widget(Paragraph::new(text), inner);
+
+    // Nur die Optionen sind klickbar; ein Klick daneben bricht ab
+    app.click_regions.clear();
+    for (i, resolution) in ConflictPolicy::RESOLUTIONS.iter().enumerate() {
+        let row = Rect {
+            x: inner.x,
+            y: inner.y + 4 + i as u16,
+            width: inner.width,
+            height: 1,
+        };
+        app.click_regions
+            .push((row.intersection(inner), ClickAction::ResolveConflict(*resolution)));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -859,9 +1508,12 @@ mod tests {
     use crate::models::{Message, Session};
     use ratatui::backend::TestBackend;
     use ratatui::Terminal;
+    use std::collections::HashMap;
 
-    fn make_session(id: &str, project: &str, messages: Vec<Message>) -> Session {
-        Session {
+    /// Session summary plus its transcript; `app_with` seeds the transcript cache
+    /// so the preview renders without touching the filesystem.
+    fn make_session(id: &str, project: &str, messages: Vec<Message>) -> (Session, Vec<Message>) {
+        let session = Session {
             id: id.to_string(),
             project_path: format!("/home/g/{}", project),
             project_name: project.to_string(),
@@ -869,16 +1521,37 @@ mod tests {
             updated_at: "2026-01-15T12:00:00+01:00".to_string(),
             size: 1024,
             total_entries: messages.len() + 3,
-            messages,
-            jsonl_path: std::path::PathBuf::new(),
+            message_count: messages.len(),
+            first_prompt: crate::models::first_user_prompt(&messages),
+            started_at: None,
+            ended_at: None,
+            token_usage: Default::default(),
+            jsonl_path: std::path::PathBuf::from(format!("/home/g/{}/{}.jsonl", project, id)),
             slug: None,
+            trashed: None,
+        };
+        (session, messages)
+    }
+
+    fn app_with(sessions: Vec<(Session, Vec<Message>)>) -> App {
+        let mut app = App::with_sessions(Vec::new());
+        for (session, messages) in sessions {
+            app.transcripts.insert(&session, messages);
+            app.sessions.push(session);
         }
+        app
     }
 
     fn make_msg(role: &str, content: &str) -> Message {
         Message {
             role: role.to_string(),
             content: content.to_string(),
+            parts: vec![MessagePart::Text {
+                text: content.to_string(),
+            }],
+            timestamp: None,
+            uuid: None,
+            parent_uuid: None,
         }
     }
 
@@ -891,7 +1564,7 @@ mod tests {
 
     #[test]
     fn test_renders_session_list_header() {
-        let mut app = App::with_sessions(vec![make_session("abc12345-6789", "my-project", vec![])]);
+        let mut app = app_with(vec![make_session("abc12345-6789", "my-project", vec![])]);
         let output = render_to_string(&mut app, 100, 20);
         assert!(output.contains("Sessions"), "Should show Sessions tab");
         assert!(output.contains("my-project"), "Should show project name");
@@ -899,7 +1572,7 @@ mod tests {
 
     #[test]
     fn test_renders_message_count() {
-        let mut app = App::with_sessions(vec![make_session(
+        let mut app = app_with(vec![make_session(
             "abc12345-6789",
             "test-proj",
             vec![make_msg("user", "Hello"), make_msg("assistant", "Hi there")],
@@ -910,7 +1583,7 @@ mod tests {
 
     #[test]
     fn test_renders_preview_for_selected_session() {
-        let mut app = App::with_sessions(vec![make_session(
+        let mut app = app_with(vec![make_session(
             "abc12345-6789",
             "my-project",
             vec![
@@ -928,7 +1601,7 @@ mod tests {
 
     #[test]
     fn test_renders_empty_state() {
-        let mut app = App::with_sessions(vec![]);
+        let mut app = app_with(vec![]);
         let output = render_to_string(&mut app, 100, 20);
         assert!(output.contains("Sessions (0)"), "Should show 0 sessions");
         assert!(
@@ -939,7 +1612,7 @@ mod tests {
 
     #[test]
     fn test_selection_moves_preview() {
-        let mut app = App::with_sessions(vec![
+        let mut app = app_with(vec![
             make_session(
                 "aaa11111-0000",
                 "first-project",
@@ -970,7 +1643,7 @@ mod tests {
 
     #[test]
     fn test_search_modal_renders() {
-        let mut app = App::with_sessions(vec![make_session("abc12345-6789", "my-project", vec![])]);
+        let mut app = app_with(vec![make_session("abc12345-6789", "my-project", vec![])]);
         app.show_search = true;
         app.search_query = "test".to_string();
 
@@ -979,9 +1652,91 @@ mod tests {
         assert!(output.contains("test"), "Should show search query");
     }
 
+    #[test]
+    fn test_export_picker_renders_formats() {
+        let mut app = app_with(vec![make_session("abc12345-6789", "my-project", vec![])]);
+        app.config.export_format = ExportFormat::Json;
+        app.open_export_picker();
+
+        let output = render_to_string(&mut app, 100, 20);
+        assert!(output.contains("Export as"));
+        assert!(output.contains("Plain text") && output.contains(".txt"));
+        assert!(output.contains("▸3  JSON"), "Standardformat ist vorausgewählt:\n{output}");
+        assert!(output.contains("[Tab] Selected session"));
+        assert!(output.contains("Redact secrets: off"));
+        assert_eq!(app.click_regions.len(), ExportFormat::ALL.len() + 2);
+
+        app.cycle_export_scope();
+        let output = render_to_string(&mut app, 100, 20);
+        assert!(output.contains("All 1 listed sessions"), "{output}");
+    }
+
+    #[test]
+    fn test_secret_scan_marks_rows_and_lists_findings() {
+        let mut app = app_with(vec![
+            make_session("abc12345-6789", "leaky-project", vec![]),
+            make_session("def67890-1234", "clean-project", vec![]),
+        ]);
+        let finding = crate::redact::SecretFinding {
+            message_index: 4,
+            detector: "github-token",
+            snippet: "export GH=ghp_********".to_string(),
+        };
+        let path = app.sessions[0].jsonl_path.clone();
+        app.secret_findings = Some(HashMap::from([(path, vec![finding])]));
+
+        let output = render_to_string(&mut app, 120, 24);
+        assert!(output.contains("⚠ leaky"), "{output}");
+        assert!(!output.contains("⚠ clean"));
+
+        app.show_secret_scan = true;
+        let output = render_to_string(&mut app, 120, 24);
+        assert!(output.contains("Secret scan"));
+        assert!(output.contains("leaky-project (abc12345)  (1 finding(s))"), "{output}");
+        assert!(output.contains("#5    github-token    export GH=ghp_********"), "{output}");
+        assert!(app.click_regions.is_empty(), "Modal deckt die normalen Regionen ab");
+    }
+
+    #[test]
+    fn test_conflict_modal_offers_resolutions() {
+        let mut app = app_with(vec![make_session("abc12345", "proj", vec![])]);
+        app.conflict = Some(crate::app::PendingConflict {
+            session_id: "abc12345".to_string(),
+            operation: ConflictOperation::Restore,
+            existing: "/home/g/.claude/projects/proj/abc12345.jsonl".into(),
+        });
+        let output = render_to_string(&mut app, 120, 24);
+        assert!(output.contains("Already exists"));
+        assert!(output.contains("Restore abc12345 over"));
+        assert!(output.contains("/home/g/.claude/projects/proj/abc12345.jsonl"));
+        assert!(output.contains("[k] Keep both (new ID)"));
+        assert!(output.contains("[m] Merge transcripts"));
+        let actions: Vec<_> = app.click_regions.iter().map(|(_, a)| a.clone()).collect();
+        assert_eq!(
+            actions,
+            ConflictPolicy::RESOLUTIONS.map(ClickAction::ResolveConflict).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_checkbox_column_while_marking() {
+        let mut app = app_with(vec![
+            make_session("aaaa1111", "proj", vec![]),
+            make_session("bbbb2222", "proj", vec![]),
+        ]);
+        let output = render_to_string(&mut app, 160, 20);
+        assert!(!output.contains("[ ]") && output.contains("Sessions (2)"));
+
+        app.marked.insert("bbbb2222".to_string());
+        let output = render_to_string(&mut app, 160, 20);
+        assert!(output.contains("Sessions (2, 1 marked)"));
+        assert!(output.contains("[x]"));
+        assert!(output.contains("[ ]"));
+    }
+
     #[test]
     fn test_truncated_session_id_in_list() {
-        let mut app = App::with_sessions(vec![make_session(
+        let mut app = app_with(vec![make_session(
             "abcdef12-3456-7890-abcd-ef1234567890",
             "proj",
             vec![],
@@ -1000,7 +1755,7 @@ mod tests {
 
     #[test]
     fn test_commands_bar_shows_keybindings() {
-        let mut app = App::with_sessions(vec![make_session("abc12345-6789", "my-project", vec![])]);
+        let mut app = app_with(vec![make_session("abc12345-6789", "my-project", vec![])]);
         let output = render_to_string(&mut app, 100, 20);
         assert!(output.contains("run"), "Should show run command");
         assert!(output.contains("rename"), "Should show rename command");
@@ -1038,7 +1793,7 @@ mod tests {
 
     #[test]
     fn test_preview_with_problematic_unicode_renders_clean() {
-        let mut app = App::with_sessions(vec![make_session(
+        let mut app = app_with(vec![make_session(
             "abc12345-6789",
             "project",
             vec![make_msg("user", "⛁ Active 30+ ⛁ files ⛶ custom stack")],
@@ -1050,7 +1805,7 @@ mod tests {
 
     #[test]
     fn test_preview_shows_entries_and_messages() {
-        let mut app = App::with_sessions(vec![make_session(
+        let mut app = app_with(vec![make_session(
             "abc12345-6789",
             "my-project",
             vec![make_msg("user", "Hello")],
@@ -1061,11 +1816,154 @@ mod tests {
         assert!(output.contains("Entries:"), "Should show Entries label");
     }
 
+    #[test]
+    fn test_preview_collapses_tool_output_by_default() {
+        let tool_call = Message {
+            role: "assistant".to_string(),
+            content: String::new(),
+            parts: vec![MessagePart::ToolUse {
+                id: "t1".to_string(),
+                name: "Bash".to_string(),
+                input: serde_json::json!({"command": "cargo build"}),
+            }],
+            ..make_msg("assistant", "")
+        };
+        let tool_result = Message {
+            role: "user".to_string(),
+            content: String::new(),
+            parts: vec![MessagePart::ToolResult {
+                tool_use_id: "t1".to_string(),
+                is_error: false,
+                output: "Compiling foo\nFinished".to_string(),
+            }],
+            ..make_msg("user", "")
+        };
+        let mut app = app_with(vec![make_session(
+            "abc12345-6789",
+            "my-project",
+            vec![tool_call, tool_result],
+        )]);
+
+        let output = render_to_string(&mut app, 120, 30);
+        assert!(output.contains("▸ Bash cargo build"), "tool call shown: {output}");
+        assert!(output.contains("◂ output (2 lines, o to expand)"));
+        assert!(!output.contains("Compiling foo"), "output collapsed by default");
+        assert!(!output.contains("You:"), "tool result has no speaker line");
+
+        app.toggle_tool_output();
+        let output = render_to_string(&mut app, 120, 30);
+        assert!(output.contains("Compiling foo"), "output visible after toggle");
+    }
+
+    #[test]
+    fn test_preview_shows_turn_times_and_duration() {
+        let mut first = make_msg("user", "start");
+        first.timestamp = Some("2026-01-15T10:00:00Z".to_string());
+        let mut last = make_msg("assistant", "done");
+        last.timestamp = Some("2026-01-15T11:05:00Z".to_string());
+        let (mut session, msgs) = make_session("abc12345-6789", "my-project", vec![first, last]);
+        session.started_at = Some("2026-01-15T10:00:00Z".to_string());
+        session.ended_at = Some("2026-01-15T11:05:00Z".to_string());
+        let mut app = app_with(vec![(session, msgs)]);
+
+        let output = render_to_string(&mut app, 140, 30);
+        assert!(output.contains("Duration: 1h 05m"), "{output}");
+        let start = format_timestamp("2026-01-15T10:00:00Z");
+        assert!(output.contains(&format!("▶ You: {}", start)), "{output}");
+    }
+
+    #[test]
+    fn test_preview_shows_active_branch_and_switches() {
+        let linked = |uuid: &str, parent: Option<&str>, role: &str, content: &str| Message {
+            uuid: Some(uuid.to_string()),
+            parent_uuid: parent.map(String::from),
+            ..make_msg(role, content)
+        };
+        let mut app = app_with(vec![make_session(
+            "abc12345-6789",
+            "my-project",
+            vec![
+                linked("a", None, "user", "Fix the bug"),
+                linked("b", Some("a"), "assistant", "Old attempt"),
+                linked("c", Some("a"), "assistant", "Better attempt"),
+            ],
+        )]);
+
+        let output = render_to_string(&mut app, 120, 30);
+        assert!(output.contains("Branch: 2/2"), "{output}");
+        assert!(output.contains("alternative 2/2"));
+        assert!(output.contains("Better attempt"));
+        assert!(!output.contains("Old attempt"), "inactive branch hidden");
+
+        app.cycle_branch(false);
+        let output = render_to_string(&mut app, 120, 30);
+        assert!(output.contains("Branch: 1/2"));
+        assert!(output.contains("Old attempt"));
+        assert!(!output.contains("Better attempt"));
+    }
+
+    #[test]
+    fn test_stats_tab_renders_dashboard() {
+        let (mut big, big_msgs) = make_session("big-session", "alpha", vec![make_msg("user", "hi")]);
+        big.size = 5 * 1024 * 1024;
+        let mut app = app_with(vec![
+            (big, big_msgs),
+            make_session("small-1", "beta", vec![make_msg("user", "a")]),
+            make_session("small-2", "beta", vec![make_msg("user", "b")]),
+        ]);
+        app.disk_usage = crate::store::DiskUsage {
+            projects: 4 * 1024 * 1024,
+            trash: 1024 * 1024,
+        };
+        app.switch_to_tab(Tab::Stats);
+        let output = render_to_string(&mut app, 140, 40);
+
+        for title in ["Totals", "Projects", "Sessions per day", "Sessions per week", "Busiest hours", "Largest sessions"] {
+            assert!(output.contains(title), "missing {title}: {output}");
+        }
+        assert!(output.contains("● 3 Stats"));
+        assert!(output.contains("Sessions:  3"));
+        assert!(output.contains("Trash:     1.0 MB  (20% of 5.0 MB)"));
+        // beta has more sessions and is listed first
+        assert!(output.find("beta").unwrap() < output.find("alpha").unwrap());
+        assert!(output.contains("5.0 MB  alpha"));
+    }
+
+    #[test]
+    fn test_token_and_cost_breakdown() {
+        let (mut session, msgs) = make_session("abc12345-6789", "my-project", vec![make_msg("user", "hi")]);
+        session.token_usage.insert(
+            "claude-opus-4".to_string(),
+            crate::models::TokenUsage {
+                input: 1_000,
+                output: 2_500,
+                cache_creation: 0,
+                cache_read: 10_000,
+            },
+        );
+        let mut app = app_with(vec![(session, msgs)]);
+        let output = render_to_string(&mut app, 160, 30);
+        assert!(output.contains("Tokens") && output.contains("Cost"), "header has both columns");
+        assert!(output.contains("13.5k"), "list and preview show total: {output}");
+        assert!(output.contains("claude-opus-4: 13.5k · $0.22"), "{output}");
+        assert!(output.contains("Cost:   ~$0.22  (project: ~$0.22)"));
+        assert!(output.contains("in 1.0k · out 2.5k · cache write 0 · cache read 10.0k"));
+    }
+
+    #[test]
+    fn test_narrow_list_drops_usage_columns() {
+        let mut app = app_with(vec![make_session("abc12345-6789", "my-project", vec![])]);
+        let output = render_to_string(&mut app, 100, 20);
+        assert!(output.contains("Date ▼"), "sort arrow stays visible: {output}");
+        assert!(output.contains("2026-01-15"), "{output}");
+        assert!(!output.contains("Cost"), "{output}");
+    }
+
     #[test]
     fn test_name_column_shows_custom_title() {
-        let mut s = make_session("abc12345-6789", "my-project", vec![make_msg("user", "hi")]);
+        let (mut s, msgs) = make_session("abc12345-6789", "my-project", vec![make_msg("user", "hi")]);
         s.slug = Some("my-label".to_string());
-        let mut app = App::with_sessions(vec![s]);
+        let mut app = app_with(vec![(s, msgs)]);
         let output = render_to_string(&mut app, 120, 20);
         assert!(output.contains("my-label"), "Name column should show custom title");
         assert!(output.contains("Name"), "Header should have Name column");
@@ -1073,16 +1971,16 @@ mod tests {
 
     #[test]
     fn test_preview_shows_custom_title_label() {
-        let mut s = make_session("abc12345-6789", "my-project", vec![make_msg("user", "hi")]);
+        let (mut s, msgs) = make_session("abc12345-6789", "my-project", vec![make_msg("user", "hi")]);
         s.slug = Some("renamed-session".to_string());
-        let mut app = App::with_sessions(vec![s]);
+        let mut app = app_with(vec![(s, msgs)]);
         let output = render_to_string(&mut app, 120, 20);
         assert!(output.contains("renamed-session"), "Preview should show custom title as label");
     }
 
     #[test]
     fn test_snapshot_initial_render() {
-        let mut app = App::with_sessions(vec![
+        let mut app = app_with(vec![
             make_session(
                 "abc12345-6789",
                 "my-project",
@@ -1105,7 +2003,7 @@ mod tests {
 
     #[test]
     fn test_snapshot_settings_modal() {
-        let mut app = App::with_sessions(vec![make_session(
+        let mut app = app_with(vec![make_session(
             "abc12345-6789",
             "my-project",
             vec![make_msg("user", "Hello")],
@@ -1119,7 +2017,7 @@ mod tests {
 
     #[test]
     fn test_snapshot_help_modal() {
-        let mut app = App::with_sessions(vec![make_session(
+        let mut app = app_with(vec![make_session(
             "abc12345-6789",
             "my-project",
             vec![make_msg("user", "Hello")],
@@ -1133,7 +2031,7 @@ mod tests {
 
     #[test]
     fn test_snapshot_delete_confirm() {
-        let mut app = App::with_sessions(vec![make_session(
+        let mut app = app_with(vec![make_session(
             "abc12345-6789",
             "my-project",
             vec![make_msg("user", "Hello")],
@@ -1164,6 +2062,7 @@ mod tests {
     fn test_format_size_megabytes() {
         assert_eq!(format_size(1024 * 1024), "1.0 MB");
         assert_eq!(format_size(2 * 1024 * 1024 + 512 * 1024), "2.5 MB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GB");
     }
 
     // --- format_datetime ---
@@ -1223,7 +2122,13 @@ mod tests {
     fn test_snapshot_loading_progress() {
         let backend = TestBackend::new(80, 24);
         let mut terminal = Terminal::new(backend).unwrap();
-        terminal.draw(|f| draw_loading(f, 42, 100)).unwrap();
+        let progress = LoadProgress {
+            loaded: 42,
+            total: 100,
+            bytes: 3 * 1024 * 1024,
+            failed: 2,
+        };
+        terminal.draw(|f| draw_loading(f, &progress)).unwrap();
         insta::assert_snapshot!(terminal.backend());
     }
 
@@ -1352,10 +2257,30 @@ mod tests {
     fn test_draw_loading_zero_total() {
         let backend = TestBackend::new(80, 24);
         let mut terminal = Terminal::new(backend).unwrap();
-        terminal.draw(|f| draw_loading(f, 0, 0)).unwrap();
+        terminal
+            .draw(|f| draw_loading(f, &LoadProgress::default()))
+            .unwrap();
         // Should not panic with total=0
         let output = terminal.backend().to_string();
         assert!(output.contains("0/0"));
+        assert!(!output.contains("failed"), "no failures → no failed counter");
+    }
+
+    #[test]
+    fn test_draw_loading_shows_bytes_and_failures() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let progress = LoadProgress {
+            loaded: 10,
+            total: 20,
+            bytes: 2048,
+            failed: 3,
+        };
+        terminal.draw(|f| draw_loading(f, &progress)).unwrap();
+        let output = terminal.backend().to_string();
+        assert!(output.contains("10/20"));
+        assert!(output.contains("2.0 KB parsed"));
+        assert!(output.contains("3 failed"));
     }
 
     // --- sanitize_for_display: additional ranges ---
@@ -1388,16 +2313,41 @@ mod tests {
         assert_eq!(format_datetime("2026-01-15T10:30"), "2026-01-15 10:30");
     }
 
+    #[test]
+    fn test_trash_tab_shows_deletion_record() {
+        let mut app = app_with(vec![]);
+        let (mut session, msgs) =
+            make_session("trash-session", "deleted-project", vec![make_msg("user", "bye")]);
+        session.trashed = Some(crate::models::TrashInfo {
+            deleted_at: "2026-03-02T08:15:00+01:00".to_string(),
+            original_path: "/home/g/.claude/projects/-p/trash-session.jsonl".into(),
+            title: "deleted-project [old name] (trash-se)".to_string(),
+            reason: crate::models::TrashReason::ZeroMessages,
+        });
+        app.transcripts.insert(&session, msgs);
+        app.trash = vec![session];
+        app.current_tab = crate::app::Tab::Trash;
+
+        let output = render_to_string(&mut app, 160, 24);
+        // Datumsspalte und Preview zeigen den Löschzeitpunkt statt der Dateizeit
+        assert_eq!(output.matches("2026-03-02 08:15").count(), 2, "{output}");
+        assert!(output.contains("(zero-message cleanup)"));
+        assert!(output.contains("/home/g/.claude/projects/-p/trash-session.jsonl"));
+        assert!(output.contains("deleted-project [old name] (trash-se)"));
+    }
+
     // --- Snapshot: Trash tab render ---
 
     #[test]
     fn test_snapshot_trash_tab() {
-        let mut app = App::with_sessions(vec![]);
-        app.trash = vec![make_session(
+        let mut app = app_with(vec![]);
+        let (session, msgs) = make_session(
             "trash-session",
             "deleted-project",
             vec![make_msg("user", "old message")],
-        )];
+        );
+        app.transcripts.insert(&session, msgs);
+        app.trash = vec![session];
         app.current_tab = crate::app::Tab::Trash;
         let backend = TestBackend::new(100, 20);
         let mut terminal = Terminal::new(backend).unwrap();
@@ -1409,7 +2359,7 @@ mod tests {
 
     #[test]
     fn test_snapshot_search_with_filter() {
-        let mut app = App::with_sessions(vec![
+        let mut app = app_with(vec![
             make_session(
                 "abc12345",
                 "alpha-project",
@@ -0,0 +1,161 @@
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+/// A file must be quiet this long before it is re-read. A running `claude` process
+/// appends to its JSONL constantly; without this every write would trigger a re-parse.
+const SETTLE_DELAY: Duration = Duration::from_millis(500);
+
+/// Changed JSONL paths waiting for their writes to settle.
+#[derive(Debug, Default)]
+pub struct PendingChanges {
+    last_event: HashMap<PathBuf, Instant>,
+}
+
+impl PendingChanges {
+    pub fn record(&mut self, path: PathBuf, at: Instant) {
+        if path.extension().and_then(|e| e.to_str()) == Some("jsonl") {
+            self.last_event.insert(path, at);
+        }
+    }
+
+    /// Removes and returns all paths without events during the last `SETTLE_DELAY`.
+    pub fn take_settled(&mut self, now: Instant) -> Vec<PathBuf> {
+        let settled: Vec<PathBuf> = self
+            .last_event
+            .iter()
+            .filter(|(_, at)| now.duration_since(**at) >= SETTLE_DELAY)
+            .map(|(p, _)| p.clone())
+            .collect();
+        for path in &settled {
+            self.last_event.remove(path);
+        }
+        settled
+    }
+}
+
+/// Watches the projects and trash directories for JSONL changes.
+/// Roots that do not exist yet (or get removed, e.g. by emptying the trash)
+/// are picked up again as soon as they reappear.
+pub struct SessionWatcher {
+    watcher: RecommendedWatcher,
+    rx: Receiver<notify::Result<Event>>,
+    /// Watched roots and whether a watch is currently active.
+    roots: Vec<(PathBuf, bool)>,
+    pending: PendingChanges,
+}
+
+impl SessionWatcher {
+    pub fn start(roots: &[&Path]) -> notify::Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        let watcher = notify::recommended_watcher(tx)?;
+        let mut watcher = Self {
+            watcher,
+            rx,
+            roots: roots.iter().map(|r| (r.to_path_buf(), false)).collect(),
+            pending: PendingChanges::default(),
+        };
+        watcher.refresh_roots(Instant::now(), false);
+        Ok(watcher)
+    }
+
+    /// Collects pending events and returns the paths whose changes have settled.
+    /// Never blocks; meant to be called once per iteration of the event loop.
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let now = Instant::now();
+        while let Ok(event) = self.rx.try_recv() {
+            if let Ok(event) = event {
+                for path in event.paths {
+                    self.pending.record(path, now);
+                }
+            }
+        }
+        self.refresh_roots(now, true);
+        self.pending.take_settled(now)
+    }
+
+    /// (Re-)registers roots that exist but are not watched. When `rescan` is set, the
+    /// files already inside a re-added root are queued, since their events were missed.
+    fn refresh_roots(&mut self, now: Instant, rescan: bool) {
+        for (root, active) in &mut self.roots {
+            if *active && !root.exists() {
+                let _ = self.watcher.unwatch(root);
+                *active = false;
+            } else if !*active
+                && root.is_dir()
+                && self.watcher.watch(root, RecursiveMode::Recursive).is_ok()
+            {
+                *active = true;
+                if rescan {
+                    for path in jsonl_files(root) {
+                        self.pending.record(path, now);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `<root>/<project>/*.jsonl`, ignoring unreadable directories.
+fn jsonl_files(root: &Path) -> Vec<PathBuf> {
+    let Ok(projects) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    projects
+        .flatten()
+        .filter_map(|p| fs::read_dir(p.path()).ok())
+        .flat_map(|files| files.flatten().map(|f| f.path()))
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("jsonl"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_pending_changes_wait_for_settle_delay() {
+        let mut pending = PendingChanges::default();
+        let t0 = Instant::now();
+        pending.record(PathBuf::from("/p/a.jsonl"), t0);
+        pending.record(PathBuf::from("/p/notes.txt"), t0);
+
+        assert!(pending.take_settled(t0 + SETTLE_DELAY / 2).is_empty());
+        // A new write restarts the delay
+        pending.record(PathBuf::from("/p/a.jsonl"), t0 + SETTLE_DELAY / 2);
+        assert!(pending.take_settled(t0 + SETTLE_DELAY).is_empty());
+
+        let settled = pending.take_settled(t0 + SETTLE_DELAY * 2);
+        assert_eq!(settled, vec![PathBuf::from("/p/a.jsonl")]);
+        assert!(pending.take_settled(t0 + SETTLE_DELAY * 3).is_empty());
+    }
+
+    #[test]
+    fn test_watcher_reports_new_file_and_late_root() {
+        let tmp = TempDir::new().unwrap();
+        let projects = tmp.path().join("projects");
+        let trash = tmp.path().join("trash");
+        fs::create_dir_all(projects.join("-p")).unwrap();
+        let mut watcher = SessionWatcher::start(&[&projects, &trash]).unwrap();
+
+        let session = projects.join("-p/s1.jsonl");
+        fs::write(&session, "{}").unwrap();
+        // Trash did not exist at startup; files moved into it must still be reported
+        fs::create_dir_all(trash.join("-p")).unwrap();
+        let trashed = trash.join("-p/t1.jsonl");
+        fs::write(&trashed, "{}").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut seen = Vec::new();
+        while Instant::now() < deadline && !(seen.contains(&session) && seen.contains(&trashed)) {
+            seen.extend(watcher.poll());
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert!(seen.contains(&session), "new session file reported: {:?}", seen);
+        assert!(seen.contains(&trashed), "file in late trash root reported: {:?}", seen);
+    }
+}
@@ -234,7 +234,7 @@ test.describe('restore from trash', () => {
     await expect(
       terminal.getByText('restore-me', { strict: false }),
     ).toBeVisible()
-    terminal.write('u')
+    terminal.write('r')
     await expect(
       terminal.getByText('Restored', { strict: false }),
     ).toBeVisible()